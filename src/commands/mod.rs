@@ -1,23 +1,58 @@
+// Helpers shared by several commands
+mod operate;
+mod transliteration;
+mod width;
+
 // Command modules should be added here
+mod str_chars;
+mod str_columns;
 mod str_compress;
+mod str_confusables;
 mod str_decompress;
 mod str_dedent;
 mod str_deunicode;
+mod str_expand_tabs;
+mod str_filename;
+mod str_hyphenate;
 mod str_indent;
+mod str_indentation_convert;
+mod str_indentation_detect;
+mod str_normalize;
+mod str_pad;
+mod str_sanitize;
 mod str_shlquote;
 mod str_shlsplit;
 mod str_similarity;
 mod str_slug;
+mod str_suggest;
+mod str_truncate;
+mod str_unexpand_tabs;
+mod str_width;
 mod str_wrap;
 
 // Command structs should be exported here
+pub use str_chars::StrChars;
+pub use str_columns::StrColumns;
 pub use str_compress::StrCompress;
+pub use str_confusables::StrConfusables;
 pub use str_decompress::StrDecompress;
 pub use str_dedent::StrDedent;
 pub use str_deunicode::StrDeunicode;
+pub use str_expand_tabs::StrExpandTabs;
+pub use str_filename::StrFilename;
+pub use str_hyphenate::StrHyphenate;
 pub use str_indent::StrIndent;
+pub use str_indentation_convert::StrIndentationConvert;
+pub use str_indentation_detect::StrIndentationDetect;
+pub use str_normalize::StrNormalize;
+pub use str_pad::StrPad;
+pub use str_sanitize::StrSanitize;
 pub use str_shlquote::StrShlQuote;
 pub use str_shlsplit::StrShlSplit;
 pub use str_similarity::StrSimilarity;
 pub use str_slug::StrSlug;
+pub use str_suggest::StrSuggest;
+pub use str_truncate::StrTruncate;
+pub use str_unexpand_tabs::StrUnexpandTabs;
+pub use str_width::StrWidth;
 pub use str_wrap::StrWrap;
@@ -0,0 +1,31 @@
+use nu_protocol::ast::CellPath;
+use nu_protocol::{Span, Value};
+
+/// Apply `f` to the input, or with cell paths to the values at those paths. Lists are mapped
+/// element by element, so a table with cell paths updates those columns in every row.
+pub(crate) fn operate(
+    input: &Value,
+    paths: &[CellPath],
+    head: Span,
+    f: &dyn Fn(&Value) -> Value,
+) -> Value {
+    match input {
+        Value::List { vals, .. } => Value::list(
+            vals.iter()
+                .map(|val| operate(val, paths, head, f))
+                .collect(),
+            head,
+        ),
+        Value::Error { .. } => input.clone(),
+        _ if paths.is_empty() => f(input),
+        _ => {
+            let mut value = input.clone();
+            for path in paths {
+                if let Err(error) = value.update_cell_path(&path.members, Box::new(f)) {
+                    return Value::error(error, head);
+                }
+            }
+            value
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use super::width::display_width;
+use deunicode::{deunicode, deunicode_char};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Type, Value, record,
+};
+use unicode_general_category::{GeneralCategory, get_general_category};
+use unicode_script::UnicodeScript;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::StrutilsPlugin;
+
+pub struct StrChars;
+
+impl SimplePluginCommand for StrChars {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str chars"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![(Type::String, Type::table())])
+            .switch(
+                "graphemes",
+                "Return one row per grapheme cluster instead of per code point.",
+                Some('g'),
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Inspect the characters of a string one by one."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"Each row has the character, its code point, Unicode name, general category, script, display width, UTF-8 bytes in hex and the str deunicode replacement. Characters without a name, such as control characters, are shown with a label like <control-000A>, and the replacement is empty for characters deunicode doesn't know.
+
+With --graphemes, the code points, names, categories and scripts of the characters in a grapheme cluster are joined together."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec![
+            "unicode",
+            "codepoint",
+            "inspect",
+            "debug",
+            "name",
+            "category",
+            "script",
+            "grapheme",
+        ]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Find a no-break space",
+                example: r#""a\u{a0}" | str chars"#,
+                result: Some(Value::test_list(vec![
+                    Value::test_record(record! {
+                        "char" => Value::test_string("a"),
+                        "codepoint" => Value::test_string("U+0061"),
+                        "name" => Value::test_string("LATIN SMALL LETTER A"),
+                        "category" => Value::test_string("Ll"),
+                        "script" => Value::test_string("Latin"),
+                        "width" => Value::test_int(1),
+                        "utf8" => Value::test_string("61"),
+                        "deunicode" => Value::test_string("a"),
+                    }),
+                    Value::test_record(record! {
+                        "char" => Value::test_string("\u{a0}"),
+                        "codepoint" => Value::test_string("U+00A0"),
+                        "name" => Value::test_string("NO-BREAK SPACE"),
+                        "category" => Value::test_string("Zs"),
+                        "script" => Value::test_string("Common"),
+                        "width" => Value::test_int(1),
+                        "utf8" => Value::test_string("C2 A0"),
+                        "deunicode" => Value::test_string(" "),
+                    }),
+                ])),
+            },
+            Example {
+                description: "Find invisible format characters such as zero width spaces",
+                example: r#""pay\u{200b}pal" | str chars | where category == Cf"#,
+                result: None,
+            },
+            Example {
+                description: "Inspect a grapheme cluster",
+                example: r#""e\u{301}" | str chars --graphemes"#,
+                result: Some(Value::test_list(vec![Value::test_record(record! {
+                    "char" => Value::test_string("e\u{301}"),
+                    "codepoint" => Value::test_string("U+0065 U+0301"),
+                    "name" => Value::test_string("LATIN SMALL LETTER E + COMBINING ACUTE ACCENT"),
+                    "category" => Value::test_string("Ll Mn"),
+                    "script" => Value::test_string("Latin Inherited"),
+                    "width" => Value::test_int(1),
+                    "utf8" => Value::test_string("65 CC 81"),
+                    "deunicode" => Value::test_string("e"),
+                })])),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let graphemes = call.has_flag("graphemes")?;
+
+        Ok(do_chars(input, graphemes, call.head))
+    }
+}
+
+fn do_chars(input: &Value, graphemes: bool, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => {
+            let rows = if graphemes {
+                val.graphemes(true).map(|g| char_row(g, head)).collect()
+            } else {
+                val.char_indices()
+                    .map(|(i, c)| char_row(&val[i..i + c.len_utf8()], head))
+                    .collect()
+            };
+            Value::list(rows, head)
+        }
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+/// A row describing a single character or grapheme cluster.
+fn char_row(text: &str, head: Span) -> Value {
+    let join = |f: &dyn Fn(char) -> String, separator: &str| {
+        text.chars().map(f).collect::<Vec<_>>().join(separator)
+    };
+    let replacement = match text.chars().all(|c| deunicode_char(c).is_some()) {
+        true => deunicode(text),
+        false => String::new(),
+    };
+
+    Value::record(
+        record! {
+            "char" => Value::string(text, head),
+            "codepoint" => Value::string(join(&|c| format!("U+{:04X}", c as u32), " "), head),
+            "name" => Value::string(join(&char_name, " + "), head),
+            "category" => Value::string(
+                join(&|c| get_general_category(c).abbreviation().into(), " "),
+                head,
+            ),
+            "script" => Value::string(join(&|c| c.script().full_name().into(), " "), head),
+            "width" => Value::int(display_width(text) as i64, head),
+            "utf8" => Value::string(
+                text.bytes().map(|b| format!("{b:02X}")).collect::<Vec<_>>().join(" "),
+                head,
+            ),
+            "deunicode" => Value::string(replacement, head),
+        },
+        head,
+    )
+}
+
+/// The Unicode name of a character, or a code point label such as `<control-0009>` for
+/// characters that have no name.
+pub(crate) fn char_name(c: char) -> String {
+    if let Some(name) = unicode_names2::name(c) {
+        return name.to_string();
+    }
+    let label = match get_general_category(c) {
+        GeneralCategory::Control => "control",
+        GeneralCategory::PrivateUse => "private-use",
+        GeneralCategory::Surrogate => "surrogate",
+        _ if is_noncharacter(c) => "noncharacter",
+        GeneralCategory::Unassigned => "reserved",
+        _ => "unnamed",
+    };
+
+    format!("<{label}-{:04X}>", c as u32)
+}
+
+/// Whether `c` is one of the 66 code points permanently reserved as noncharacters.
+pub(crate) fn is_noncharacter(c: char) -> bool {
+    matches!(c, '\u{FDD0}'..='\u{FDEF}') || (c as u32) & 0xFFFE == 0xFFFE
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrChars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_name_labels() {
+        assert_eq!(char_name('\t'), "<control-0009>");
+        assert_eq!(char_name('\u{E000}'), "<private-use-E000>");
+        assert_eq!(char_name('\u{FFFF}'), "<noncharacter-FFFF>");
+        assert_eq!(char_name('\u{378}'), "<reserved-0378>");
+        assert_eq!(char_name('中'), "CJK UNIFIED IDEOGRAPH-4E2D");
+    }
+
+    #[test]
+    fn test_unknown_replacement() {
+        let deunicode = |text| {
+            let row = char_row(text, Span::test_data());
+            row.get_data_by_key("deunicode").unwrap()
+        };
+        assert_eq!(deunicode("\u{E000}"), Value::test_string(""));
+        assert_eq!(deunicode("é"), Value::test_string("e"));
+    }
+}
@@ -0,0 +1,343 @@
+use super::str_wrap::{Alignment, resolve_width, terminal_width, wrap_text};
+use super::width::display_width;
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, SyntaxShape, Type, Value,
+};
+use textwrap::Options;
+
+use crate::StrutilsPlugin;
+
+pub struct StrColumns;
+
+impl SimplePluginCommand for StrColumns {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str columns"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::String, Type::String),
+                (Type::List(Box::new(Type::Any)), Type::String),
+            ])
+            .named(
+                "columns",
+                SyntaxShape::Int,
+                "Number of columns. (default as many as fit in the width)",
+                Some('c'),
+            )
+            .named(
+                "width",
+                SyntaxShape::OneOf(vec![SyntaxShape::Int, SyntaxShape::String]),
+                "Total width in columns, an offset from the terminal width like -4, or a percentage of it like 50%. (default terminal width, or 80 without a terminal)",
+                Some('w'),
+            )
+            .named(
+                "gutter",
+                SyntaxShape::OneOf(vec![SyntaxShape::Int, SyntaxShape::String]),
+                "Separator between columns, or a number of spaces. (default 2 spaces)",
+                Some('g'),
+            )
+            .switch(
+                "row-major",
+                "Fill the columns across each row instead of down each column.",
+                Some('r'),
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Lay out text or a list in newspaper-style columns."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"A list is laid out like a grid, with each item in its own cell and every column as wide as its widest item. A string is split into lines; with --columns, the text is first wrapped to the width of one column so a paragraph flows down the columns like a newspaper.
+
+Widths are measured in display columns, so CJK, emoji and ANSI colored text line up."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["grid", "newspaper", "layout", "wrap", "column"]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Lay out a list in as many columns as fit in 20 columns",
+                example: "[one two three four five six seven] | str columns --width 20",
+                result: Some(Value::test_string(
+                    "one    four  seven\ntwo    five\nthree  six",
+                )),
+            },
+            Example {
+                description: "Fill the columns across each row",
+                example: "[one two three four five six seven] | str columns --width 20 --row-major",
+                result: Some(Value::test_string(
+                    "one    two   three\nfour   five  six\nseven",
+                )),
+            },
+            Example {
+                description: "Flow a paragraph down two columns separated by a bar",
+                example: r#""now is the time for all good men to come to the aid of their country" | str columns --columns 2 --width 33 --gutter " | ""#,
+                result: Some(Value::test_string(
+                    "now is the time | to the aid of\nfor all good    | their country\nmen to come",
+                )),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let width = match call.get_flag("width")? {
+            Some(width) => resolve_width(&width, || terminal_width(engine, call.head))?,
+            None => terminal_width(engine, call.head),
+        };
+        let columns = match call.get_flag::<Value>("columns")? {
+            Some(value) => match value.as_int()? {
+                columns if columns > 0 => Some(columns as usize),
+                _ => {
+                    return Err(LabeledError::new("Invalid number of columns")
+                        .with_label("expected a positive number", value.span()));
+                }
+            },
+            None => None,
+        };
+        let gutter = match call.get_flag::<Value>("gutter")? {
+            Some(Value::Int { val, .. }) if val < 0 => {
+                return Err(LabeledError::new("Invalid gutter")
+                    .with_label("expected a non-negative number", call.head));
+            }
+            Some(Value::Int { val, .. }) => " ".repeat(val as usize),
+            Some(gutter) => gutter.coerce_into_string()?,
+            None => "  ".to_string(),
+        };
+        let grid = Grid {
+            width,
+            columns,
+            gutter,
+            row_major: call.has_flag("row-major")?,
+        };
+
+        Ok(do_columns(input, &grid, call.head))
+    }
+}
+
+struct Grid {
+    width: usize,
+    columns: Option<usize>,
+    gutter: String,
+    row_major: bool,
+}
+
+fn do_columns(input: &Value, grid: &Grid, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => {
+            let text = match grid.columns {
+                Some(columns) => {
+                    // every column of a paragraph gets the same width so the text lines up
+                    let width = column_width(grid.width, columns, &grid.gutter);
+                    let wrapped = wrap_text(val, &Options::new(width), Alignment::Left);
+                    let lines: Vec<String> = wrapped.lines().map(str::to_string).collect();
+                    layout(&lines, grid, Some(width))
+                }
+                None => {
+                    let lines: Vec<String> = val.lines().map(str::to_string).collect();
+                    layout(&lines, grid, None)
+                }
+            };
+            Value::string(text, head)
+        }
+        Value::List { vals, .. } => {
+            let mut items = Vec::with_capacity(vals.len());
+            for val in vals {
+                match val {
+                    Value::Error { .. } => return val.clone(),
+                    _ => match val.coerce_string() {
+                        Ok(item) => items.push(item),
+                        Err(_) => return type_error(val, head),
+                    },
+                }
+            }
+            Value::string(layout(&items, grid, None), head)
+        }
+        Value::Error { .. } => input.clone(),
+        _ => type_error(input, head),
+    }
+}
+
+fn type_error(input: &Value, head: Span) -> Value {
+    Value::error(
+        ShellError::OnlySupportsThisInputType {
+            exp_input_type: "string or list<string>".into(),
+            wrong_type: input.get_type().to_string(),
+            dst_span: head,
+            src_span: input.span(),
+        },
+        head,
+    )
+}
+
+/// Width of each of `columns` equal columns that fit in `width` with the gutters between them.
+fn column_width(width: usize, columns: usize, gutter: &str) -> usize {
+    let gutters = display_width(gutter) * (columns - 1);
+    (width.saturating_sub(gutters) / columns).max(1)
+}
+
+/// Arrange `items` in columns and render them, padding every column but the last to the width
+/// of its widest item, or to `fixed` when given.
+fn layout(items: &[String], grid: &Grid, fixed: Option<usize>) -> String {
+    if items.is_empty() {
+        return String::new();
+    }
+    let widths: Vec<usize> = items.iter().map(|item| display_width(item)).collect();
+    let gutter = display_width(&grid.gutter);
+
+    // every column is at least as wide as the narrowest item, which bounds how many can fit;
+    // try the most columns from there and take the first arrangement that fits
+    let narrowest = widths.iter().copied().min().unwrap_or(0);
+    let most = match narrowest + gutter {
+        0 => items.len(),
+        step => ((grid.width + gutter) / step).clamp(1, items.len()),
+    };
+    let cells = match grid.columns {
+        Some(columns) => arrange(items.len(), columns, grid.row_major),
+        None => (1..=most)
+            .rev()
+            .map(|columns| arrange(items.len(), columns, grid.row_major))
+            .find(|cells| {
+                let total = cells
+                    .iter()
+                    .map(|column| column.iter().map(|&i| widths[i]).max().unwrap_or(0))
+                    .sum::<usize>();
+                total + gutter * (cells.len() - 1) <= grid.width
+            })
+            .unwrap_or_else(|| arrange(items.len(), 1, grid.row_major)),
+    };
+    let column_widths: Vec<usize> = cells
+        .iter()
+        .map(|column| fixed.unwrap_or_else(|| column.iter().map(|&i| widths[i]).max().unwrap_or(0)))
+        .collect();
+
+    let rows = cells.iter().map(Vec::len).max().unwrap_or(0);
+    (0..rows)
+        .map(|row| {
+            let mut line = String::new();
+            let last = cells.iter().rposition(|column| row < column.len());
+            for (c, column) in cells.iter().enumerate() {
+                if c > 0 {
+                    line.push_str(&grid.gutter);
+                }
+                let item = column.get(row).map_or("", |&i| items[i].as_str());
+                line.push_str(item);
+                if Some(c) == last {
+                    break;
+                }
+                let width = column.get(row).map_or(0, |&i| widths[i]);
+                line.push_str(&" ".repeat(column_widths[c].saturating_sub(width)));
+            }
+            line.trim_end_matches(' ').to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Indices of the items in each column, filling down each column or across each row.
+fn arrange(len: usize, columns: usize, row_major: bool) -> Vec<Vec<usize>> {
+    let columns = columns.clamp(1, len.max(1));
+    let rows = len.div_ceil(columns);
+    if row_major {
+        (0..columns)
+            .map(|c| (c..len).step_by(columns).collect())
+            .collect()
+    } else {
+        // filling down can leave trailing columns empty, so only keep the ones in use
+        (0..len.div_ceil(rows))
+            .map(|c| (c * rows..((c + 1) * rows).min(len)).collect())
+            .collect()
+    }
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrColumns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(width: usize, columns: Option<usize>, row_major: bool) -> Grid {
+        Grid {
+            width,
+            columns,
+            gutter: "  ".to_string(),
+            row_major,
+        }
+    }
+
+    fn items(words: &str) -> Vec<String> {
+        words.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_arrange() {
+        assert_eq!(
+            arrange(7, 3, false),
+            [vec![0, 1, 2], vec![3, 4, 5], vec![6]]
+        );
+        assert_eq!(arrange(7, 3, true), [vec![0, 3, 6], vec![1, 4], vec![2, 5]]);
+        // four items down four columns of two rows only need two columns
+        assert_eq!(arrange(4, 3, false), [vec![0, 1], vec![2, 3]]);
+        assert_eq!(arrange(2, 5, true), [vec![0], vec![1]]);
+    }
+
+    #[test]
+    fn test_as_many_columns_as_fit() {
+        let words = items("a bb ccc dddd");
+        assert_eq!(
+            layout(&words, &grid(80, None, false), None),
+            "a  bb  ccc  dddd"
+        );
+        assert_eq!(
+            layout(&words, &grid(12, None, false), None),
+            "a   ccc\nbb  dddd"
+        );
+        assert_eq!(
+            layout(&words, &grid(1, None, false), None),
+            "a\nbb\nccc\ndddd"
+        );
+        // many items start from the most columns the narrowest one allows
+        let words = vec!["x".to_string(); 10_000];
+        let output = layout(&words, &grid(80, None, false), None);
+        assert_eq!(output.lines().count(), 371);
+        assert_eq!(
+            output.lines().next(),
+            Some(vec!["x"; 27].join("  ").as_str())
+        );
+    }
+
+    #[test]
+    fn test_padding_uses_display_width() {
+        let words = items("日本 a \x1b[31mred\x1b[0m b");
+        assert_eq!(
+            layout(&words, &grid(80, Some(2), true), None),
+            "日本  a\n\x1b[31mred\x1b[0m   b"
+        );
+        // an emoji joined with zero width joiners is as wide as in str width
+        let words = items("👨‍👩‍👧 a ab b");
+        assert_eq!(
+            layout(&words, &grid(80, Some(2), true), None),
+            "👨‍👩‍👧  a\nab  b"
+        );
+    }
+}
@@ -84,13 +84,16 @@ impl SimplePluginCommand for StrCompress {
         call: &EvaluatedCall,
         input: &Value,
     ) -> Result<Value, LabeledError> {
-        fn to_u32(n: Spanned<i64>) -> Result<Spanned<u32>, ShellError> {
+        fn to_u32(n: Spanned<i64>) -> Result<Spanned<u32>, LabeledError> {
             u32::try_from(n.item)
-                .map_err(|err| ShellError::CantConvert {
-                    to_type: "u32".into(),
-                    from_type: "int".into(),
-                    span: n.span,
-                    help: Some(err.to_string()),
+                .map_err(|err| {
+                    ShellError::CantConvert {
+                        to_type: "u32".into(),
+                        from_type: "int".into(),
+                        span: n.span,
+                        help: Some(err.to_string()),
+                    }
+                    .into()
                 })
                 .map(|o| o.into_spanned(n.span))
         }
@@ -211,7 +214,11 @@ fn do_brotli(
     Ok(Value::binary(out_buf, value_span))
 }
 
-fn write_value(out: &mut impl std::io::Write, value: String, span: Span) -> Result<(), ShellError> {
+fn write_value(
+    out: &mut impl std::io::Write,
+    value: String,
+    span: Span,
+) -> Result<(), LabeledError> {
     out.write_all(value.as_bytes()).map_err(|err| {
         ShellError::Generic(GenericError::new(
             err.to_string(),
@@ -0,0 +1,174 @@
+use super::operate::operate;
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::ast::CellPath;
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, SyntaxShape, Type, Value, record,
+};
+use unicode_security::{MixedScript, skeleton};
+
+use crate::StrutilsPlugin;
+
+pub struct StrConfusables;
+
+impl SimplePluginCommand for StrConfusables {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str confusables"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::String, Type::String),
+                (Type::String, Type::Bool),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::String)),
+                ),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::Bool)),
+                ),
+                (Type::table(), Type::table()),
+                (Type::record(), Type::record()),
+            ])
+            .allow_variants_without_examples(true)
+            .rest(
+                "rest",
+                SyntaxShape::CellPath,
+                "For a data structure input, check the strings at the given cell paths.",
+            )
+            .named(
+                "check",
+                SyntaxShape::String,
+                "Return whether the input is visually confusable with this string.",
+                Some('c'),
+            )
+            .switch(
+                "mixed-script",
+                "Return whether the input mixes letters of different scripts.",
+                Some('m'),
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Compute the confusable skeleton of strings to detect homoglyph spoofing."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"The skeleton is defined by Unicode Technical Standard #39: characters that look alike, such as Latin a and Cyrillic а or rn and m, map to the same prototype, so two strings are confusable when their skeletons are equal. Skeletons are meant for comparison and not for display.
+
+With --mixed-script, a string is mixed when no single script covers all of its letters. Characters shared by all scripts, such as digits and punctuation, don't count, and Han with Hiragana, Katakana or Hangul is a single script."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec![
+            "homoglyph",
+            "skeleton",
+            "spoof",
+            "confusable",
+            "tr39",
+            "security",
+            "script",
+        ]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Compute the skeleton of a string with a Cyrillic а",
+                example: r#""pаypal" | str confusables"#,
+                result: Some(Value::test_string("paypal")),
+            },
+            Example {
+                description: "Check whether two strings are confusable",
+                example: r#""rnicrosoft" | str confusables --check "microsoft""#,
+                result: Some(Value::test_bool(true)),
+            },
+            Example {
+                description: "Flag strings that mix scripts",
+                example: r#"["paypal" "pаypal" "東京タワー"] | str confusables --mixed-script"#,
+                result: Some(Value::test_list(vec![
+                    Value::test_bool(false),
+                    Value::test_bool(true),
+                    Value::test_bool(false),
+                ])),
+            },
+            Example {
+                description: "Flag usernames that mix scripts",
+                example: r#"[[user]; [admin] ["аdmin"]] | str confusables --mixed-script user"#,
+                result: Some(Value::test_list(vec![
+                    Value::test_record(record! {
+                        "user" => Value::test_bool(false),
+                    }),
+                    Value::test_record(record! {
+                        "user" => Value::test_bool(true),
+                    }),
+                ])),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let paths: Vec<CellPath> = call.rest(0)?;
+        let check: Option<String> = call.get_flag("check")?;
+        let mode = match (check, call.has_flag("mixed-script")?) {
+            (Some(_), true) => {
+                return Err(LabeledError::new(
+                    "Only one of --check and --mixed-script can be used",
+                )
+                .with_label("conflicting flags", call.head));
+            }
+            (Some(other), false) => Mode::Check(skeleton(&other).collect()),
+            (None, true) => Mode::MixedScript,
+            (None, false) => Mode::Skeleton,
+        };
+        let head = call.head;
+
+        Ok(operate(input, &paths, head, &|value| {
+            do_confusables(value, &mode, head)
+        }))
+    }
+}
+
+enum Mode {
+    Skeleton,
+    /// Compare against the skeleton of another string.
+    Check(String),
+    MixedScript,
+}
+
+fn do_confusables(input: &Value, mode: &Mode, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => match mode {
+            Mode::Skeleton => Value::string(skeleton(val).collect::<String>(), head),
+            Mode::Check(other) => Value::bool(skeleton(val).eq(other.chars()), head),
+            Mode::MixedScript => Value::bool(!val.as_str().is_single_script(), head),
+        },
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrConfusables)
+}
@@ -1,6 +1,8 @@
 use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
-use nu_protocol::{Category, Example, LabeledError, ShellError, Signature, Span, Type, Value};
-use textwrap::dedent;
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type, Value,
+};
+use std::borrow::Cow;
 
 use crate::StrutilsPlugin;
 
@@ -16,6 +18,28 @@ impl SimplePluginCommand for StrDedent {
     fn signature(&self) -> Signature {
         Signature::build(self.name())
             .input_output_types(vec![(Type::String, Type::String)])
+            .named(
+                "tab-width",
+                SyntaxShape::Int,
+                "Expand tabs in the indentation to this many columns before measuring it.",
+                Some('t'),
+            )
+            .switch(
+                "ignore-first-line",
+                "Leave the first line as it is and don't count it when measuring the indentation.",
+                Some('f'),
+            )
+            .switch(
+                "trim-blank-edges",
+                "Drop blank lines at the start and end of the text.",
+                Some('b'),
+            )
+            .named(
+                "max",
+                SyntaxShape::Int,
+                "Remove at most this many columns of indentation.",
+                Some('m'),
+            )
             .category(Category::Strings)
     }
 
@@ -28,11 +52,33 @@ impl SimplePluginCommand for StrDedent {
     }
 
     fn examples(&self) -> Vec<Example<'_>> {
-        vec![Example {
-            description: "Dedent string",
-            example: "'     1st line\r\n       2nd line\r\n     3rd line\r\n' | str dedent",
-            result: Some(Value::test_string("1st line\n  2nd line\n3rd line\n")),
-        }]
+        vec![
+            Example {
+                description: "Dedent string",
+                example: "'     1st line\r\n       2nd line\r\n     3rd line\r\n' | str dedent",
+                result: Some(Value::test_string("1st line\n  2nd line\n3rd line\n")),
+            },
+            Example {
+                description: "Dedent a multi-line string literal, dropping the blank first and last lines",
+                example: r#""\n    foo\n      bar\n  " | str dedent --trim-blank-edges"#,
+                result: Some(Value::test_string("foo\n  bar")),
+            },
+            Example {
+                description: "Dedent lines indented with a mix of tabs and spaces",
+                example: r#""\tfoo\n    bar" | str dedent --tab-width 4"#,
+                result: Some(Value::test_string("foo\nbar")),
+            },
+            Example {
+                description: "Dedent all but the first line",
+                example: r#""foo\n    bar\n    baz" | str dedent --ignore-first-line"#,
+                result: Some(Value::test_string("foo\nbar\nbaz")),
+            },
+            Example {
+                description: "Remove at most 4 columns of indentation",
+                example: r#""        foo\n          bar" | str dedent --max 4"#,
+                result: Some(Value::test_string("    foo\n      bar")),
+            },
+        ]
     }
 
     fn run(
@@ -42,13 +88,41 @@ impl SimplePluginCommand for StrDedent {
         call: &EvaluatedCall,
         input: &Value,
     ) -> Result<Value, LabeledError> {
-        Ok(do_dedent(input, call.head))
+        let tab_width: Option<Spanned<i64>> = call.get_flag("tab-width")?;
+        let max: Option<Spanned<i64>> = call.get_flag("max")?;
+        let options = Dedent {
+            tab_width: tab_width
+                .map(|width| match width.item {
+                    1.. => Ok(width.item as usize),
+                    _ => Err(LabeledError::new("Invalid tab width")
+                        .with_label("expected a positive number", width.span)),
+                })
+                .transpose()?,
+            ignore_first_line: call.has_flag("ignore-first-line")?,
+            trim_blank_edges: call.has_flag("trim-blank-edges")?,
+            max: max
+                .map(|max| match max.item {
+                    0.. => Ok(max.item as usize),
+                    _ => Err(LabeledError::new("Invalid maximum")
+                        .with_label("expected a non-negative number", max.span)),
+                })
+                .transpose()?,
+        };
+
+        Ok(do_dedent(input, &options, call.head))
     }
 }
 
-fn do_dedent(input: &Value, head: Span) -> Value {
+struct Dedent {
+    tab_width: Option<usize>,
+    ignore_first_line: bool,
+    trim_blank_edges: bool,
+    max: Option<usize>,
+}
+
+fn do_dedent(input: &Value, options: &Dedent, head: Span) -> Value {
     match input {
-        Value::String { val, .. } => Value::string(dedent(val), head),
+        Value::String { val, .. } => Value::string(dedent(val, options), head),
         Value::Error { .. } => input.clone(),
         _ => Value::error(
             ShellError::OnlySupportsThisInputType {
@@ -62,6 +136,108 @@ fn do_dedent(input: &Value, head: Span) -> Value {
     }
 }
 
+/// Remove the whitespace prefix common to all non-blank lines, like `textwrap::dedent`. Lines
+/// that are only whitespace become empty.
+fn dedent(text: &str, options: &Dedent) -> String {
+    let mut lines: Vec<&str> = text.lines().collect();
+    let mut trailing_newline = text.ends_with('\n');
+    let is_blank = |line: &&str| line.trim().is_empty();
+    if options.trim_blank_edges {
+        let start = lines.iter().position(|line| !is_blank(line)).unwrap_or(0);
+        let end = lines
+            .iter()
+            .rposition(|line| !is_blank(line))
+            .map_or(0, |i| i + 1);
+        lines = lines[start..end.max(start)].to_vec();
+        trailing_newline = false;
+    }
+
+    let skip = usize::from(options.ignore_first_line).min(lines.len());
+    let rest: Vec<Cow<str>> = lines[skip..]
+        .iter()
+        .map(|line| match options.tab_width {
+            Some(tab_width) => expand_indent(line, tab_width),
+            None => Cow::Borrowed(*line),
+        })
+        .collect();
+
+    let mut prefix = common_prefix(rest.iter().map(|line| line.as_ref()));
+    if let Some(max) = options.max {
+        let end = prefix
+            .char_indices()
+            .nth(max)
+            .map_or(prefix.len(), |(i, _)| i);
+        prefix = &prefix[..end];
+    }
+
+    let mut result = lines[..skip]
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    result.extend(rest.iter().map(|line| match line.strip_prefix(prefix) {
+        _ if is_blank(&line.as_ref()) => String::new(),
+        Some(tail) => tail.to_string(),
+        None => line.to_string(),
+    }));
+    let mut result = result.join("\n");
+    if trailing_newline {
+        result.push('\n');
+    }
+
+    result
+}
+
+/// The leading whitespace shared by every line that isn't blank.
+fn common_prefix<'a>(lines: impl Iterator<Item = &'a str>) -> &'a str {
+    let mut prefix: Option<&str> = None;
+    for line in lines {
+        let content = line.trim_start();
+        if content.is_empty() {
+            continue;
+        }
+        let indent = &line[..line.len() - content.len()];
+        prefix = Some(match prefix {
+            Some(prefix) => {
+                let end = prefix
+                    .char_indices()
+                    .zip(indent.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map_or(prefix.len().min(indent.len()), |((i, _), _)| i);
+                &prefix[..end]
+            }
+            None => indent,
+        });
+    }
+    prefix.unwrap_or_default()
+}
+
+/// Replace the tabs in the indentation of `line` with spaces up to the next tab stop.
+fn expand_indent(line: &str, tab_width: usize) -> Cow<'_, str> {
+    let content = line.trim_start();
+    let indent = &line[..line.len() - content.len()];
+    if !indent.contains('\t') {
+        return Cow::Borrowed(line);
+    }
+
+    let mut expanded = String::with_capacity(line.len());
+    let mut column = 0;
+    for c in indent.chars() {
+        match c {
+            '\t' => {
+                let spaces = tab_width - column % tab_width;
+                expanded.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            }
+            _ => {
+                expanded.push(c);
+                column += 1;
+            }
+        }
+    }
+    expanded.push_str(content);
+    Cow::Owned(expanded)
+}
+
 #[test]
 fn test_examples() -> Result<(), nu_protocol::ShellError> {
     use nu_plugin_test_support::PluginTest;
@@ -74,3 +250,51 @@ fn test_examples() -> Result<(), nu_protocol::ShellError> {
 
     PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrDedent)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: Dedent = Dedent {
+        tab_width: None,
+        ignore_first_line: false,
+        trim_blank_edges: false,
+        max: None,
+    };
+
+    #[test]
+    fn test_default_matches_textwrap() {
+        for text in [
+            "",
+            "\n",
+            "  a\n    b\n\n  c\n",
+            "\t a\n\t  b\n \t c",
+            "    \n  a\n   \n",
+            "  a\r\n  b",
+            "no indent\n  here",
+        ] {
+            assert_eq!(dedent(text, &DEFAULT), textwrap::dedent(text), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn test_expand_indent() {
+        assert_eq!(expand_indent("\tx", 4), "    x");
+        assert_eq!(expand_indent("  \tx\ty", 4), "    x\ty");
+        assert_eq!(expand_indent("x", 4), "x");
+    }
+
+    #[test]
+    fn test_options_combine() {
+        let options = Dedent {
+            tab_width: Some(8),
+            ignore_first_line: true,
+            trim_blank_edges: true,
+            max: Some(6),
+        };
+        assert_eq!(
+            dedent("\n\n  first\n\t\tsecond\n\t    third\n\n", &options),
+            "  first\n          second\n      third"
+        );
+    }
+}
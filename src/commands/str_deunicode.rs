@@ -1,6 +1,11 @@
-use deunicode::deunicode;
+use super::transliteration::{Language, language};
+use deunicode::deunicode_with_tofu;
 use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
-use nu_protocol::{Category, Example, LabeledError, ShellError, Signature, Span, Type, Value};
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, SyntaxShape, Type, Value,
+};
+use std::ops::RangeInclusive;
+use unicode_script::{Script, UnicodeScript};
 
 use crate::StrutilsPlugin;
 
@@ -16,6 +21,38 @@ impl SimplePluginCommand for StrDeunicode {
     fn signature(&self) -> Signature {
         Signature::build(self.name())
             .input_output_types(vec![(Type::String, Type::String)])
+            .named(
+                "placeholder",
+                SyntaxShape::String,
+                "Replacement for characters that have no ASCII equivalent. (default [?])",
+                Some('p'),
+            )
+            .named(
+                "keep",
+                SyntaxShape::OneOf(vec![
+                    SyntaxShape::List(Box::new(SyntaxShape::String)),
+                    SyntaxShape::String,
+                ]),
+                "Characters or ranges such as a-z or U+0400-U+04FF to leave untouched.",
+                Some('k'),
+            )
+            .named(
+                "lang",
+                SyntaxShape::String,
+                "Transliterate with the rules of a language: de, da, nb, no, el, ru, uk, bg or iso9 for Cyrillic.",
+                None,
+            )
+            .named(
+                "mapping",
+                SyntaxShape::Record(vec![]),
+                "Replacements for characters or sequences that override all other rules.",
+                Some('m'),
+            )
+            .switch(
+                "only-latin",
+                "Only transliterate Latin script letters and leave other scripts alone.",
+                Some('l'),
+            )
             .category(Category::Strings)
     }
 
@@ -23,16 +60,71 @@ impl SimplePluginCommand for StrDeunicode {
         "Convert Unicode string to pure ASCII."
     }
 
+    fn extra_description(&self) -> &str {
+        r"Each --keep item is a single character, a range of two characters or code points joined by a dash, or otherwise a set of characters. Kept characters are passed through as they are.
+
+The --lang rules replace letters following the usual romanization of the language, such as ü as ue in German, and keep the case of the text, so ЩУКА becomes SHCHUKA. Russian follows BGN/PCGN, Ukrainian its national system, Bulgarian the streamlined system and Greek ELOT 743, all without diacritics, while iso9 follows the ASCII variant of ISO 9 for the letters of Russian, Ukrainian, Belarusian, Bulgarian, Serbian and Macedonian. Replacements from --mapping are inserted as given and take precedence over --keep and --lang.
+
+With --only-latin, combining marks that follow a Latin letter are transliterated along with it, and characters of other scripts as well as symbols and punctuation outside ASCII are left as they are."
+    }
+
     fn search_terms(&self) -> Vec<&str> {
-        vec!["convert", "ascii"]
+        vec!["convert", "ascii", "transliterate", "romanize", "language"]
     }
 
     fn examples(&self) -> Vec<Example<'_>> {
-        vec![Example {
-            description: "deunicode a string",
-            example: "'A…C' | str deunicode",
-            result: Some(Value::test_string("A...C")),
-        }]
+        vec![
+            Example {
+                description: "deunicode a string",
+                example: "'A…C' | str deunicode",
+                result: Some(Value::test_string("A...C")),
+            },
+            Example {
+                description: "Replace characters without an ASCII equivalent with a placeholder",
+                example: r#""a\u{E000}b" | str deunicode --placeholder "?""#,
+                result: Some(Value::test_string("a?b")),
+            },
+            Example {
+                description: "Keep some symbols as they are",
+                example: r#""Café: 20 €, 5 °C" | str deunicode --keep "€°""#,
+                result: Some(Value::test_string("Cafe: 20 €, 5 °C")),
+            },
+            Example {
+                description: "Keep a range of code points and a single character",
+                example: r#""Ωmega ü €" | str deunicode --keep [U+0391-U+03C9 €]"#,
+                result: Some(Value::test_string("Ωmega u €")),
+            },
+            Example {
+                description: "Transliterate German umlauts",
+                example: r#""Müller, Größe" | str deunicode --lang de"#,
+                result: Some(Value::test_string("Mueller, Groesse")),
+            },
+            Example {
+                description: "Transliterate Russian with BGN/PCGN",
+                example: r#""Щукин, Елена" | str deunicode --lang ru"#,
+                result: Some(Value::test_string("Shchukin, Yelena")),
+            },
+            Example {
+                description: "Transliterate Cyrillic with ISO 9",
+                example: r#""Щукин, Елена" | str deunicode --lang iso9"#,
+                result: Some(Value::test_string("Shhukin, Elena")),
+            },
+            Example {
+                description: "Transliterate Greek",
+                example: r#""Θεσσαλονίκη" | str deunicode --lang el"#,
+                result: Some(Value::test_string("Thessaloniki")),
+            },
+            Example {
+                description: "Add project specific replacements",
+                example: r#""5 € & ß" | str deunicode --lang de --mapping {"€": EUR, "&": and}"#,
+                result: Some(Value::test_string("5 EUR and ss")),
+            },
+            Example {
+                description: "Only transliterate Latin script",
+                example: r#""Crème brûlée: крем-брюле" | str deunicode --only-latin"#,
+                result: Some(Value::test_string("Creme brulee: крем-брюле")),
+            },
+        ]
     }
 
     fn run(
@@ -42,13 +134,170 @@ impl SimplePluginCommand for StrDeunicode {
         call: &EvaluatedCall,
         input: &Value,
     ) -> Result<Value, LabeledError> {
-        Ok(do_deunicode(input, call.head))
+        let deunicode = Deunicode {
+            placeholder: call
+                .get_flag("placeholder")?
+                .unwrap_or_else(|| "[?]".into()),
+            keep: keep_ranges(call.get_flag("keep")?)?,
+            language: match call.get_flag("lang")? {
+                Some(code) => Some(language(&code)?),
+                None => None,
+            },
+            mapping: mapping(call.get_flag("mapping")?)?,
+            only_latin: call.has_flag("only-latin")?,
+        };
+
+        Ok(do_deunicode(input, &deunicode, call.head))
     }
 }
 
-fn do_deunicode(input: &Value, head: Span) -> Value {
+struct Deunicode {
+    placeholder: String,
+    keep: Vec<RangeInclusive<char>>,
+    language: Option<&'static Language>,
+    mapping: Vec<(String, String)>,
+    only_latin: bool,
+}
+
+impl Deunicode {
+    /// Transliterate the runs of characters between the ones that are passed through or replaced
+    /// by a rule, so that deunicode still sees whole words.
+    fn apply(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        // start of the run of characters not yet transliterated
+        let mut run = 0;
+        let mut previous = false;
+        let mut i = 0;
+        while let Some(c) = text[i..].chars().next() {
+            let (len, replacement) = match self.rule(text, i) {
+                Some((len, replacement)) => (len, replacement),
+                None if self.converts(c, previous) => {
+                    previous = true;
+                    i += c.len_utf8();
+                    continue;
+                }
+                None => (c.len_utf8(), text[i..i + c.len_utf8()].to_string()),
+            };
+            result.push_str(&deunicode_with_tofu(&text[run..i], &self.placeholder));
+            result.push_str(&replacement);
+            previous = false;
+            i += len;
+            run = i;
+        }
+        result.push_str(&deunicode_with_tofu(&text[run..], &self.placeholder));
+
+        result
+    }
+
+    /// The replacement from `--mapping` or the language rules for the text at byte `i`, and the
+    /// number of bytes it replaces.
+    fn rule(&self, text: &str, i: usize) -> Option<(usize, String)> {
+        let rest = &text[i..];
+        let mapped = self
+            .mapping
+            .iter()
+            .filter(|(key, _)| rest.starts_with(key.as_str()))
+            .max_by_key(|(key, _)| key.len());
+        if let Some((key, replacement)) = mapped {
+            return Some((key.len(), replacement.clone()));
+        }
+        let c = rest.chars().next()?;
+        if c.is_ascii() || self.keep.iter().any(|range| range.contains(&c)) {
+            return None;
+        }
+
+        self.language?.replace(text, i)
+    }
+
+    /// Whether `c` is transliterated, given whether the character before it was.
+    fn converts(&self, c: char, previous: bool) -> bool {
+        if self.keep.iter().any(|range| range.contains(&c)) {
+            return false;
+        }
+        !self.only_latin
+            || c.is_ascii()
+            || match c.script() {
+                Script::Latin => true,
+                Script::Inherited => previous,
+                _ => false,
+            }
+    }
+}
+
+/// Parse the `--keep` flag into ranges of characters.
+fn keep_ranges(value: Option<Value>) -> Result<Vec<RangeInclusive<char>>, LabeledError> {
+    let items = match value {
+        None => return Ok(vec![]),
+        Some(Value::List { vals, .. }) => vals,
+        Some(value) => vec![value],
+    };
+
+    let mut ranges = vec![];
+    for item in items {
+        let span = item.span();
+        let item = item.coerce_into_string()?;
+        match keep_item(&item) {
+            Some(item) => ranges.extend(item),
+            None => {
+                return Err(LabeledError::new("Invalid character range")
+                    .with_label("the start of the range is after its end", span));
+            }
+        }
+    }
+
+    Ok(ranges)
+}
+
+fn keep_item(item: &str) -> Option<Vec<RangeInclusive<char>>> {
+    let bound = |bound: &str| {
+        let mut chars = bound.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => code_point(bound),
+        }
+    };
+    if let Some((start, end)) = item.split_once('-')
+        && let (Some(start), Some(end)) = (bound(start), bound(end))
+    {
+        return (start <= end).then(|| vec![start..=end]);
+    }
+    if let Some(c) = code_point(item) {
+        return Some(vec![c..=c]);
+    }
+
+    Some(item.chars().map(|c| c..=c).collect())
+}
+
+/// Parse the `--mapping` record into pairs of text and replacement.
+fn mapping(value: Option<Value>) -> Result<Vec<(String, String)>, LabeledError> {
+    let Some(value) = value else {
+        return Ok(vec![]);
+    };
+    let span = value.span();
+    let record = value.into_record()?;
+    record
+        .into_iter()
+        .map(|(key, replacement)| {
+            if key.is_empty() {
+                return Err(LabeledError::new("Invalid mapping")
+                    .with_label("the characters to replace can't be empty", span));
+            }
+            Ok((key, replacement.coerce_into_string()?))
+        })
+        .collect()
+}
+
+/// Parse a code point written as U+XXXX.
+fn code_point(text: &str) -> Option<char> {
+    let hex = text
+        .strip_prefix("U+")
+        .or_else(|| text.strip_prefix("u+"))?;
+    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+}
+
+fn do_deunicode(input: &Value, deunicode: &Deunicode, head: Span) -> Value {
     match input {
-        Value::String { val, .. } => Value::string(deunicode(val), head),
+        Value::String { val, .. } => Value::string(deunicode.apply(val), head),
         Value::Error { .. } => input.clone(),
         _ => Value::error(
             ShellError::OnlySupportsThisInputType {
@@ -74,3 +323,44 @@ fn test_examples() -> Result<(), nu_protocol::ShellError> {
 
     PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrDeunicode)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deunicode(keep: &str, only_latin: bool) -> Deunicode {
+        Deunicode {
+            placeholder: "[?]".into(),
+            keep: keep_item(keep).unwrap(),
+            language: None,
+            mapping: vec![],
+            only_latin,
+        }
+    }
+
+    #[test]
+    fn test_keep_item() {
+        assert_eq!(keep_item("a-z"), Some(vec!['a'..='z']));
+        assert_eq!(
+            keep_item("U+0400-U+04FF"),
+            Some(vec!['\u{400}'..='\u{4FF}'])
+        );
+        assert_eq!(keep_item("U+20AC"), Some(vec!['€'..='€']));
+        assert_eq!(keep_item("-"), Some(vec!['-'..='-']));
+        assert_eq!(keep_item("€-"), Some(vec!['€'..='€', '-'..='-']));
+        assert_eq!(keep_item("z-a"), None);
+    }
+
+    #[test]
+    fn test_apply() {
+        // the default matches deunicode itself
+        let text = "北京 © 2024 Æsop…";
+        assert_eq!(deunicode("", false).apply(text), deunicode::deunicode(text));
+        assert_eq!(
+            deunicode("©", false).apply(text),
+            "Bei Jing © 2024 AEsop..."
+        );
+        // combining marks go with the letter they follow
+        assert_eq!(deunicode("", true).apply("e\u{301} ά"), "e ά");
+    }
+}
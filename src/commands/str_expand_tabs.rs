@@ -0,0 +1,226 @@
+use super::width::{escape_len, next_grapheme};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, SyntaxShape, Type, Value,
+};
+
+use crate::StrutilsPlugin;
+
+pub struct StrExpandTabs;
+
+impl SimplePluginCommand for StrExpandTabs {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str expand-tabs"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![(Type::String, Type::String)])
+            .named(
+                "tabs",
+                SyntaxShape::OneOf(vec![
+                    SyntaxShape::Int,
+                    SyntaxShape::List(Box::new(SyntaxShape::Int)),
+                ]),
+                "Tab stops every n columns, or a list of tab stop columns. (default 8)",
+                Some('t'),
+            )
+            .switch(
+                "leading-only",
+                "Only expand tabs in the indentation at the start of each line.",
+                Some('l'),
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Replace tabs with spaces up to the next tab stop."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"Like the expand command from coreutils. Columns are counted in display width, so wide characters such as CJK take two columns and ANSI escape sequences take none. With a list of tab stops, tabs after the last stop become a single space."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["expand", "tab", "spaces", "untab", "column"]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Expand tabs to tab stops every 4 columns",
+                example: r#""a\tbc\td\n\tindented" | str expand-tabs --tabs 4"#,
+                result: Some(Value::test_string("a   bc  d\n    indented")),
+            },
+            Example {
+                description: "Line up columns at the given tab stops",
+                example: r#""name\tsize\tmodified" | str expand-tabs --tabs [8 16]"#,
+                result: Some(Value::test_string("name    size    modified")),
+            },
+            Example {
+                description: "Count wide characters as two columns",
+                example: r#""日本\tx" | str expand-tabs --tabs 8"#,
+                result: Some(Value::test_string("日本    x")),
+            },
+            Example {
+                description: "Only expand the indentation",
+                example: r#""\tkey\tvalue" | str expand-tabs --tabs 4 --leading-only"#,
+                result: Some(Value::test_string("    key\tvalue")),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let stops = TabStops::from_flag(call.get_flag("tabs")?)?;
+        let leading_only = call.has_flag("leading-only")?;
+
+        Ok(do_expand(input, &stops, leading_only, call.head))
+    }
+}
+
+/// Tab stops, either every n columns or at a list of columns.
+pub(crate) enum TabStops {
+    Every(usize),
+    At(Vec<usize>),
+}
+
+impl TabStops {
+    /// Parse a `--tabs` flag of a positive int or a strictly increasing list of columns.
+    pub(crate) fn from_flag(value: Option<Value>) -> Result<Self, LabeledError> {
+        let Some(value) = value else {
+            return Ok(TabStops::Every(8));
+        };
+        let invalid = || {
+            LabeledError::new("Invalid tab stops").with_label(
+                "expected a positive number or an increasing list of columns",
+                value.span(),
+            )
+        };
+        match &value {
+            Value::Int { val, .. } if *val > 0 => Ok(TabStops::Every(*val as usize)),
+            Value::List { vals, .. } if !vals.is_empty() => {
+                let stops = vals
+                    .iter()
+                    .map(|stop| stop.as_int().ok().and_then(|s| usize::try_from(s).ok()))
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|stops| stops[0] > 0 && stops.windows(2).all(|w| w[0] < w[1]))
+                    .ok_or_else(invalid)?;
+                Ok(TabStops::At(stops))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// The first tab stop after `column`, if there is one.
+    pub(crate) fn next(&self, column: usize) -> Option<usize> {
+        match self {
+            TabStops::Every(width) => Some(column + width - column % width),
+            TabStops::At(stops) => stops.iter().copied().find(|&stop| stop > column),
+        }
+    }
+}
+
+fn do_expand(input: &Value, stops: &TabStops, leading_only: bool, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => Value::string(expand(val, stops, leading_only), head),
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+fn expand(text: &str, stops: &TabStops, leading_only: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let mut column = 0;
+        let mut leading = true;
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            let len = match c {
+                '\x1b' => {
+                    let len = escape_len(rest);
+                    result.push_str(&rest[..len]);
+                    len
+                }
+                '\t' => {
+                    let stop = stops.next(column).unwrap_or(column + 1);
+                    if leading || !leading_only {
+                        result.extend(std::iter::repeat_n(' ', stop - column));
+                    } else {
+                        result.push('\t');
+                    }
+                    column = stop;
+                    1
+                }
+                _ => {
+                    let (grapheme, width) = next_grapheme(rest);
+                    leading &= grapheme == " ";
+                    column += width;
+                    result.push_str(grapheme);
+                    grapheme.len()
+                }
+            };
+            rest = &rest[len..];
+        }
+    }
+
+    result
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrExpandTabs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let every = TabStops::Every(8);
+        assert_eq!(
+            expand("\ta\tb\n12345678\tc", &every, false),
+            "        a       b\n12345678        c"
+        );
+        assert_eq!(
+            expand("\x1b[31mred\x1b[0m\tx", &every, false),
+            "\x1b[31mred\x1b[0m     x"
+        );
+        assert_eq!(expand("👨‍👩‍👧\tx", &every, false), "👨‍👩‍👧      x");
+        // after the last listed stop a tab is a single space
+        let at = TabStops::At(vec![2, 4]);
+        assert_eq!(expand("\t\t\tx", &at, false), "     x");
+    }
+
+    #[test]
+    fn test_tab_stops() {
+        let list = |stops: &[i64]| {
+            Some(Value::test_list(
+                stops.iter().map(|&stop| Value::test_int(stop)).collect(),
+            ))
+        };
+        assert!(matches!(TabStops::from_flag(None), Ok(TabStops::Every(8))));
+        assert!(TabStops::from_flag(Some(Value::test_int(0))).is_err());
+        assert!(TabStops::from_flag(list(&[4, 8, 20])).is_ok());
+        assert!(TabStops::from_flag(list(&[8, 4])).is_err());
+        assert!(TabStops::from_flag(list(&[0, 4])).is_err());
+    }
+}
@@ -0,0 +1,351 @@
+use super::operate::operate;
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::ast::CellPath;
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type,
+    Value, record,
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::StrutilsPlugin;
+
+pub struct StrFilename;
+
+impl SimplePluginCommand for StrFilename {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str filename"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::String, Type::String),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::String)),
+                ),
+                (Type::table(), Type::table()),
+                (Type::record(), Type::record()),
+            ])
+            .allow_variants_without_examples(true)
+            .rest(
+                "rest",
+                SyntaxShape::CellPath,
+                "For a data structure input, sanitize the strings at the given cell paths.",
+            )
+            .named(
+                "platform",
+                SyntaxShape::String,
+                "Platform whose rules to follow: all, windows, macos or posix. (default all)",
+                Some('p'),
+            )
+            .named(
+                "replace",
+                SyntaxShape::String,
+                "Replace each invalid character with this string instead of removing it.",
+                Some('r'),
+            )
+            .named(
+                "max-length",
+                SyntaxShape::Int,
+                "Maximum length of the name in bytes. (default 255)",
+                Some('m'),
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Turn strings into file names that are valid on every platform, or on a chosen one."
+    }
+
+    fn extra_description(&self) -> &str {
+        r#"Unlike str slug, only what would make the name invalid is changed. The rules are:
+  posix: no / and no NUL, and the name can't be . or ..
+  macos: also no :
+  windows: also no < > : " \ | ? * or control characters, no trailing dots or spaces, and no reserved device names such as CON, NUL, COM1 or LPT1, even with an extension
+  all: the rules of every platform, so the name is portable
+
+Reserved names get a _ in front. Names longer than --max-length are cut between grapheme clusters, keeping the extension when it takes at most half of the length. 255 bytes fit the limits of common file systems, which count bytes or UTF-16 units. A name left empty becomes _."#
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec![
+            "filename", "path", "sanitize", "safe", "windows", "reserved", "invalid",
+        ]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Remove characters that are invalid in file names",
+                example: r#""report: Q1/Q2 <draft>?.txt" | str filename"#,
+                result: Some(Value::test_string("report Q1Q2 draft.txt")),
+            },
+            Example {
+                description: "Replace invalid characters instead",
+                example: r#""notes 10/12: todo" | str filename --replace _"#,
+                result: Some(Value::test_string("notes 10_12_ todo")),
+            },
+            Example {
+                description: "Avoid reserved names and trailing dots and spaces",
+                example: r#"["CON.txt" "nul" "notes. . "] | str filename"#,
+                result: Some(Value::test_list(vec![
+                    Value::test_string("_CON.txt"),
+                    Value::test_string("_nul"),
+                    Value::test_string("notes"),
+                ])),
+            },
+            Example {
+                description: "Follow only the POSIX rules",
+                example: r#""a:b\\c?.txt" | str filename --platform posix"#,
+                result: Some(Value::test_string("a:b\\c?.txt")),
+            },
+            Example {
+                description: "Limit the length and keep the extension",
+                example: r#""a very long file name.txt" | str filename --max-length 12"#,
+                result: Some(Value::test_string("a very l.txt")),
+            },
+            Example {
+                description: "Sanitize a column of a table",
+                example: r#"[[name]; ["what?.md"]] | str filename name"#,
+                result: Some(Value::test_list(vec![Value::test_record(record! {
+                    "name" => Value::test_string("what.md"),
+                })])),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let paths: Vec<CellPath> = call.rest(0)?;
+        let platform = match call.get_flag("platform")? {
+            Some(platform) => Platform::from_name(platform)?,
+            None => Platform::All,
+        };
+        let replacement = match call.get_flag::<Spanned<String>>("replace")? {
+            Some(replacement) if replacement.item.chars().any(|c| platform.is_invalid(c)) => {
+                return Err(LabeledError::new("Invalid replacement").with_label(
+                    "contains a character that is invalid in file names",
+                    replacement.span,
+                ));
+            }
+            Some(replacement) => replacement.item,
+            None => String::new(),
+        };
+        let max_length = match call.get_flag::<Spanned<i64>>("max-length")? {
+            Some(length) if length.item < 1 => {
+                return Err(LabeledError::new("Invalid maximum length")
+                    .with_label("expected a positive number", length.span));
+            }
+            Some(length) => length.item as usize,
+            None => 255,
+        };
+        let filename = Filename {
+            platform,
+            replacement,
+            max_length,
+        };
+        let head = call.head;
+
+        Ok(operate(input, &paths, head, &|value| {
+            do_filename(value, &filename, head)
+        }))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Platform {
+    All,
+    Windows,
+    Macos,
+    Posix,
+}
+
+impl Platform {
+    fn from_name(name: Spanned<String>) -> Result<Self, LabeledError> {
+        match name.item.as_str() {
+            "all" => Ok(Platform::All),
+            "windows" => Ok(Platform::Windows),
+            "macos" => Ok(Platform::Macos),
+            "posix" => Ok(Platform::Posix),
+            _ => Err(LabeledError::new("Invalid platform")
+                .with_label("expected all, windows, macos or posix", name.span)),
+        }
+    }
+
+    fn windows(self) -> bool {
+        matches!(self, Platform::All | Platform::Windows)
+    }
+
+    fn is_invalid(self, c: char) -> bool {
+        match c {
+            '/' | '\0' => true,
+            ':' => self != Platform::Posix,
+            '<' | '>' | '"' | '\\' | '|' | '?' | '*' | '\u{1}'..='\u{1f}' => self.windows(),
+            _ => false,
+        }
+    }
+}
+
+struct Filename {
+    platform: Platform,
+    replacement: String,
+    max_length: usize,
+}
+
+impl Filename {
+    fn sanitize(&self, name: &str) -> String {
+        let mut result = String::with_capacity(name.len());
+        for c in name.chars() {
+            match self.platform.is_invalid(c) {
+                true => result.push_str(&self.replacement),
+                false => result.push(c),
+            }
+        }
+
+        // a reserved name can also come from cutting a longer one, and the _ in front
+        // survives the next cut
+        loop {
+            result = self.fit(&result);
+            if !(self.platform.windows() && is_reserved(&result)) {
+                return result;
+            }
+            result.insert(0, '_');
+        }
+    }
+
+    /// Cut `name` to the maximum length and trim what the platform doesn't allow at the end.
+    fn fit(&self, name: &str) -> String {
+        let mut name = cut(name, self.max_length);
+        if self.platform.windows() {
+            name.truncate(name.trim_end_matches(['.', ' ']).len());
+        }
+        match name.as_str() {
+            "" | "." | ".." => "_".to_string(),
+            _ => name,
+        }
+    }
+}
+
+/// Cut `name` to at most `max_length` bytes between grapheme clusters, keeping an extension
+/// that takes at most half of it.
+fn cut(name: &str, max_length: usize) -> String {
+    if name.len() <= max_length {
+        return name.to_string();
+    }
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 && (name.len() - dot) * 2 <= max_length => name.split_at(dot),
+        _ => (name, ""),
+    };
+
+    let budget = max_length - extension.len();
+    let mut result = String::with_capacity(max_length);
+    for grapheme in stem.graphemes(true) {
+        if result.len() + grapheme.len() > budget {
+            break;
+        }
+        result.push_str(grapheme);
+    }
+    result.push_str(extension);
+
+    result
+}
+
+/// Whether `name` is a Windows device name, which is reserved with any extension.
+fn is_reserved(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or_default();
+    let stem = stem.trim_end_matches(' ').to_uppercase();
+    match stem.as_str() {
+        "CON" | "PRN" | "AUX" | "NUL" | "CONIN$" | "CONOUT$" => true,
+        _ => {
+            let (Some(device), Some(number)) = (stem.get(..3), stem.get(3..)) else {
+                return false;
+            };
+            let mut number = number.chars();
+            matches!(device, "COM" | "LPT")
+                && matches!(
+                    (number.next(), number.next()),
+                    (Some('0'..='9' | '¹' | '²' | '³'), None)
+                )
+        }
+    }
+}
+
+fn do_filename(input: &Value, filename: &Filename, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => Value::string(filename.sanitize(val), head),
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrFilename)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filename(platform: Platform, max_length: usize) -> Filename {
+        Filename {
+            platform,
+            replacement: String::new(),
+            max_length,
+        }
+    }
+
+    #[test]
+    fn test_reserved() {
+        for name in [
+            "CON",
+            "con.txt",
+            "Nul .tar.gz",
+            "COM1",
+            "lpt9.log",
+            "COM²",
+            "CONIN$",
+        ] {
+            assert!(is_reserved(name), "{name}");
+        }
+        for name in ["CONSOLE", "COM", "COM10", "_CON", "NULL.txt", "a.CON"] {
+            assert!(!is_reserved(name), "{name}");
+        }
+        // cutting a longer name can make it reserved
+        assert_eq!(filename(Platform::All, 3).sanitize("CONSOLE"), "_CO");
+        assert_eq!(filename(Platform::Posix, 3).sanitize("CONSOLE"), "CON");
+    }
+
+    #[test]
+    fn test_length() {
+        let long = "é".repeat(200) + ".txt";
+        let name = filename(Platform::All, 255).sanitize(&long);
+        assert_eq!(name, "é".repeat(125) + ".txt");
+        assert!(name.len() <= 255);
+        // combining marks stay with their base
+        assert_eq!(cut("e\u{301}e\u{301}", 5), "e\u{301}");
+        // an extension longer than half the length is cut too
+        assert_eq!(cut("a.verylongextension", 8), "a.verylo");
+        assert_eq!(filename(Platform::All, 255).sanitize(". . ."), "_");
+        assert_eq!(filename(Platform::Posix, 255).sanitize(".."), "_");
+        assert_eq!(filename(Platform::Posix, 255).sanitize("..."), "...");
+    }
+}
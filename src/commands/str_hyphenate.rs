@@ -0,0 +1,233 @@
+use crate::StrutilsPlugin;
+use hyphenation::{Hyphenator, Language, Load, Standard};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type, Value,
+};
+
+const SOFT_HYPHEN: &str = "\u{00ad}";
+
+/// Patterns embedded besides en-us, which comes with the hyphenation crate. They are copied from
+/// the dictionaries of hyphenation 0.8.4, so only these languages add to the size of the plugin.
+/// dictionaries/README.md explains how to update them.
+const DICTIONARIES: &[(Language, &[u8])] = &[
+    (
+        Language::EnglishGB,
+        include_bytes!("../../dictionaries/en-gb.standard.bincode"),
+    ),
+    (
+        Language::German1996,
+        include_bytes!("../../dictionaries/de-1996.standard.bincode"),
+    ),
+    (
+        Language::French,
+        include_bytes!("../../dictionaries/fr.standard.bincode"),
+    ),
+    (
+        Language::Spanish,
+        include_bytes!("../../dictionaries/es.standard.bincode"),
+    ),
+    (
+        Language::Italian,
+        include_bytes!("../../dictionaries/it.standard.bincode"),
+    ),
+    (
+        Language::Dutch,
+        include_bytes!("../../dictionaries/nl.standard.bincode"),
+    ),
+    (
+        Language::Portuguese,
+        include_bytes!("../../dictionaries/pt.standard.bincode"),
+    ),
+    (
+        Language::Russian,
+        include_bytes!("../../dictionaries/ru.standard.bincode"),
+    ),
+    (
+        Language::Swedish,
+        include_bytes!("../../dictionaries/sv.standard.bincode"),
+    ),
+];
+
+pub struct StrHyphenate;
+
+impl SimplePluginCommand for StrHyphenate {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str hyphenate"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![(Type::String, Type::String)])
+            .named(
+                "lang",
+                SyntaxShape::String,
+                "Language of the hyphenation patterns: en-us, en-gb, de-1996, fr, es, it, nl, pt, ru or sv. (default en-us)",
+                Some('l'),
+            )
+            .named(
+                "hyphen",
+                SyntaxShape::String,
+                "String inserted at each hyphenation point. (default soft hyphen U+00AD)",
+                None,
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Insert soft hyphens at the syllable boundaries of each word."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"Hyphenation points come from the Knuth-Liang patterns used by TeX. Soft hyphens are invisible unless a line is broken at them, so text can be hyphenated once and wrapped later.
+
+Languages are given by their pattern code: en-us, en-gb, de-1996, fr, es, it, nl, pt, ru or sv. Words that already contain a soft hyphen are left alone."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["syllable", "hyphen", "shy", "wrap"]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Show the hyphenation points of a word",
+                example: "'hyphenation' | str hyphenate --hyphen '-'",
+                result: Some(Value::test_string("hy-phen-a-tion")),
+            },
+            Example {
+                description: "Hyphenate German text",
+                example: "'Silbentrennung ist schwierig' | str hyphenate --lang de-1996 --hyphen '-'",
+                result: Some(Value::test_string("Sil-ben-tren-nung ist schwie-rig")),
+            },
+            Example {
+                description: "Insert soft hyphens so the text can be wrapped later",
+                example: "'internationalization' | str hyphenate",
+                result: Some(Value::test_string(
+                    "in\u{00ad}ter\u{00ad}na\u{00ad}tion\u{00ad}al\u{00ad}iza\u{00ad}tion",
+                )),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let lang: Option<Spanned<String>> = call.get_flag("lang")?;
+        let hyphen: Option<String> = call.get_flag("hyphen")?;
+        let dictionary = match lang {
+            Some(lang) => load_dictionary(&lang.item, lang.span)?,
+            None => load_dictionary("en-us", call.head)?,
+        };
+
+        Ok(do_hyphenate(
+            input,
+            &dictionary,
+            hyphen.as_deref().unwrap_or(SOFT_HYPHEN),
+            call.head,
+        ))
+    }
+}
+
+/// Load the embedded hyphenation patterns for a language code such as `en-us` or `de-1996`.
+pub(crate) fn load_dictionary(code: &str, span: Span) -> Result<Standard, LabeledError> {
+    let language = Language::try_from_code(code.to_lowercase());
+    let dictionary = match language {
+        Some(Language::EnglishUS) => Standard::from_embedded(Language::EnglishUS),
+        Some(language) => match DICTIONARIES.iter().find(|(l, _)| *l == language) {
+            Some(&(_, mut bytes)) => Standard::from_reader(language, &mut bytes),
+            None => return Err(unknown_language(code, span)),
+        },
+        None => return Err(unknown_language(code, span)),
+    };
+
+    dictionary.map_err(|err| {
+        LabeledError::new("Could not load hyphenation dictionary").with_label(err.to_string(), span)
+    })
+}
+
+fn unknown_language(code: &str, span: Span) -> LabeledError {
+    let codes: Vec<_> = std::iter::once(Language::EnglishUS)
+        .chain(DICTIONARIES.iter().map(|(language, _)| *language))
+        .map(|language| language.code())
+        .collect();
+    LabeledError::new("Unknown hyphenation language")
+        .with_label(format!("no hyphenation patterns for '{code}'"), span)
+        .with_help(format!("Use one of {}", codes.join(", ")))
+}
+
+fn do_hyphenate(input: &Value, dictionary: &Standard, hyphen: &str, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => Value::string(hyphenate(val, dictionary, hyphen), head),
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+fn hyphenate(text: &str, dictionary: &Standard, hyphen: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(char::is_alphabetic) {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !c.is_alphabetic() && c != '\u{00ad}')
+            .unwrap_or(rest.len());
+        let word = &rest[..end];
+
+        if word.contains(SOFT_HYPHEN) {
+            result.push_str(word);
+        } else {
+            let breaks = dictionary.hyphenate(word).breaks;
+            let mut last = 0;
+            for index in breaks {
+                result.push_str(&word[last..index]);
+                result.push_str(hyphen);
+                last = index;
+            }
+            result.push_str(&word[last..]);
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+
+    result
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrHyphenate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_dictionary() {
+        let span = Span::test_data();
+        for (language, _) in DICTIONARIES {
+            assert!(load_dictionary(language.code(), span).is_ok());
+        }
+        assert!(load_dictionary("EN-US", span).is_ok());
+        // known to the hyphenation crate, but not embedded
+        assert!(load_dictionary("pl", span).is_err());
+    }
+}
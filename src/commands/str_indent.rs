@@ -1,8 +1,7 @@
 use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
 use nu_protocol::{
-    Category, Example, LabeledError, ShellError, Signature, Span, SyntaxShape, Type, Value,
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type, Value,
 };
-use textwrap::indent;
 
 use crate::StrutilsPlugin;
 
@@ -18,11 +17,57 @@ impl SimplePluginCommand for StrIndent {
     fn signature(&self) -> Signature {
         Signature::build(self.name())
             .input_output_types(vec![(Type::String, Type::String)])
-            .required(
+            .optional(
                 "prefix",
                 SyntaxShape::String,
                 "Prefix used to indent each line with.",
             )
+            .named(
+                "spaces",
+                SyntaxShape::Int,
+                "Indent by this many spaces, or remove them when negative.",
+                None,
+            )
+            .named(
+                "tabs",
+                SyntaxShape::Int,
+                "Indent by this many tabs, or remove them when negative.",
+                None,
+            )
+            .named(
+                "levels",
+                SyntaxShape::Int,
+                "Indent by this many --unit levels, or remove them when negative.",
+                Some('l'),
+            )
+            .named(
+                "unit",
+                SyntaxShape::String,
+                "One level of indentation for --levels. (default 4 spaces)",
+                Some('u'),
+            )
+            .named(
+                "first-line",
+                SyntaxShape::String,
+                "Prefix for the first line, for example a bullet for a hanging indent.",
+                Some('f'),
+            )
+            .named(
+                "rest",
+                SyntaxShape::String,
+                "Prefix for every line after the first.",
+                None,
+            )
+            .switch(
+                "skip-blank",
+                "Leave lines that are empty or only whitespace as they are.",
+                Some('b'),
+            )
+            .switch(
+                "remove",
+                "Remove the prefix from the lines that start with it instead of adding it.",
+                Some('r'),
+            )
             .category(Category::Strings)
     }
 
@@ -30,16 +75,51 @@ impl SimplePluginCommand for StrIndent {
         "Indent each line by the given prefix."
     }
 
+    fn extra_description(&self) -> &str {
+        r"The indentation is the prefix argument, or a number of --spaces, --tabs or --levels. --first-line and --rest override it for the first line and the lines after it.
+
+Lines that are only whitespace get the prefix without its trailing whitespace, or nothing with --skip-blank. With --remove or a negative count, the prefix is removed from the lines that start with it."
+    }
+
     fn search_terms(&self) -> Vec<&str> {
-        vec!["convert", "ascii", "dedent", "tab"]
+        vec!["convert", "ascii", "dedent", "tab", "hanging", "outdent"]
     }
 
     fn examples(&self) -> Vec<Example<'_>> {
-        vec![Example {
-            description: "Indent each line with a provided prefix",
-            example: r#""First line.\nSecond line.\n" | str indent "1111""#,
-            result: Some(Value::test_string("1111First line.\n1111Second line.\n")),
-        }]
+        vec![
+            Example {
+                description: "Indent each line with a provided prefix",
+                example: r#""First line.\nSecond line.\n" | str indent "1111""#,
+                result: Some(Value::test_string("1111First line.\n1111Second line.\n")),
+            },
+            Example {
+                description: "Indent by two levels of two spaces",
+                example: r#""First line.\nSecond line." | str indent --levels 2 --unit "  ""#,
+                result: Some(Value::test_string("    First line.\n    Second line.")),
+            },
+            Example {
+                description: "Comment out lines, leaving blank lines empty",
+                example: r#""First line.\n\nSecond line." | str indent "// " --skip-blank"#,
+                result: Some(Value::test_string("// First line.\n\n// Second line.")),
+            },
+            Example {
+                description: "Make a list item with a hanging indent",
+                example: r#""First line.\nSecond line." | str indent --first-line "- " --rest "  ""#,
+                result: Some(Value::test_string("- First line.\n  Second line.")),
+            },
+            Example {
+                description: "Remove four spaces of indentation where present",
+                example: r#""    First line.\n      Second line.\nThird line." | str indent --spaces -4"#,
+                result: Some(Value::test_string(
+                    "First line.\n  Second line.\nThird line.",
+                )),
+            },
+            Example {
+                description: "Remove a quote prefix",
+                example: r#""> First line.\n> Second line." | str indent "> " --remove"#,
+                result: Some(Value::test_string("First line.\nSecond line.")),
+            },
+        ]
     }
 
     fn run(
@@ -49,15 +129,64 @@ impl SimplePluginCommand for StrIndent {
         call: &EvaluatedCall,
         input: &Value,
     ) -> Result<Value, LabeledError> {
-        let prefix: String = call.req(0)?;
+        let mut remove = call.has_flag("remove")?;
+        let unit: Option<String> = call.get_flag("unit")?;
+        let levels: Option<Spanned<i64>> = call.get_flag("levels")?;
+        if unit.is_some() && levels.is_none() {
+            return Err(LabeledError::new("--unit needs --levels")
+                .with_label("set the number of levels with --levels", call.head));
+        }
 
-        Ok(do_indent(input, prefix, call.head))
+        let mut prefixes: Vec<String> = call.opt(0)?.into_iter().collect();
+        let counts = [
+            (call.get_flag::<Spanned<i64>>("spaces")?, " ".to_string()),
+            (call.get_flag::<Spanned<i64>>("tabs")?, "\t".to_string()),
+            (levels, unit.unwrap_or_else(|| "    ".to_string())),
+        ];
+        for (count, unit) in counts {
+            if let Some(count) = count {
+                remove |= count.item < 0;
+                prefixes.push(unit.repeat(count.item.unsigned_abs() as usize));
+            }
+        }
+        if prefixes.len() > 1 {
+            return Err(LabeledError::new(
+                "Only one of prefix, --spaces, --tabs and --levels can be used",
+            )
+            .with_label("conflicting indentation", call.head));
+        }
+
+        let prefix = prefixes.pop();
+        let first: Option<String> = call.get_flag("first-line")?;
+        let rest: Option<String> = call.get_flag("rest")?;
+        if prefix.is_none() && first.is_none() && rest.is_none() {
+            return Err(LabeledError::new("No indentation given")
+                .with_label("missing prefix", call.head)
+                .with_help(
+                    "Pass a prefix, or one of --spaces, --tabs, --levels, --first-line or --rest",
+                ));
+        }
+        let options = Indent {
+            first: first.or_else(|| prefix.clone()).unwrap_or_default(),
+            rest: rest.or(prefix).unwrap_or_default(),
+            skip_blank: call.has_flag("skip-blank")?,
+            remove,
+        };
+
+        Ok(do_indent(input, &options, call.head))
     }
 }
 
-fn do_indent(input: &Value, prefix: String, head: Span) -> Value {
+struct Indent {
+    first: String,
+    rest: String,
+    skip_blank: bool,
+    remove: bool,
+}
+
+fn do_indent(input: &Value, options: &Indent, head: Span) -> Value {
     match input {
-        Value::String { val, .. } => Value::string(indent(val, &prefix), head),
+        Value::String { val, .. } => Value::string(indent(val, options), head),
         Value::Error { .. } => input.clone(),
         _ => Value::error(
             ShellError::OnlySupportsThisInputType {
@@ -71,6 +200,46 @@ fn do_indent(input: &Value, prefix: String, head: Span) -> Value {
     }
 }
 
+/// Add or remove the prefix of each line. Like `textwrap::indent`, lines that are only whitespace
+/// get the prefix without its trailing whitespace.
+fn indent(text: &str, options: &Indent) -> String {
+    let mut result = String::with_capacity(2 * text.len());
+    for (i, line) in text.split_terminator('\n').enumerate() {
+        if i > 0 {
+            result.push('\n');
+        }
+        let prefix = if i == 0 {
+            &options.first
+        } else {
+            &options.rest
+        };
+        let trimmed_prefix = prefix.trim_end();
+        let blank = line.trim().is_empty();
+
+        if options.remove {
+            // blank lines were indented with the trimmed prefix
+            let tail = line.strip_prefix(prefix.as_str()).or_else(|| {
+                line.strip_prefix(trimmed_prefix)
+                    .filter(|tail| tail.trim().is_empty())
+            });
+            result.push_str(tail.unwrap_or(line));
+            continue;
+        }
+        if !blank {
+            result.push_str(prefix);
+        } else if !options.skip_blank {
+            result.push_str(trimmed_prefix);
+        }
+        result.push_str(line);
+    }
+    if text.ends_with('\n') {
+        // split_terminator eats the final newline
+        result.push('\n');
+    }
+
+    result
+}
+
 #[test]
 fn test_examples() -> Result<(), nu_protocol::ShellError> {
     use nu_plugin_test_support::PluginTest;
@@ -83,3 +252,39 @@ fn test_examples() -> Result<(), nu_protocol::ShellError> {
 
     PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrIndent)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(prefix: &str, remove: bool) -> Indent {
+        Indent {
+            first: prefix.to_string(),
+            rest: prefix.to_string(),
+            skip_blank: false,
+            remove,
+        }
+    }
+
+    #[test]
+    fn test_default_matches_textwrap() {
+        for text in ["", "\n", "a\nb\n", "a\n\n  \nb", "a\r\n\r\nb"] {
+            for prefix in ["  ", "// ", "\t"] {
+                assert_eq!(
+                    indent(text, &options(prefix, false)),
+                    textwrap::indent(text, prefix),
+                    "{text:?} {prefix:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_remove_undoes_indent() {
+        let text = "a\n\n  b\n";
+        for prefix in ["    ", "// ", "> "] {
+            let indented = indent(text, &options(prefix, false));
+            assert_eq!(indent(&indented, &options(prefix, true)), text);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::str_indentation_detect::{Style, analyze, tab_width};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type, Value,
+};
+
+use crate::StrutilsPlugin;
+
+pub struct StrIndentationConvert;
+
+impl SimplePluginCommand for StrIndentationConvert {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str indentation convert"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![(Type::String, Type::String)])
+            .required_named(
+                "to",
+                SyntaxShape::String,
+                "Indentation to convert to: tabs, or spaces:<n> for n spaces per level.",
+                Some('t'),
+            )
+            .named(
+                "tab-width",
+                SyntaxShape::Int,
+                "Columns a tab counts for when measuring the input. (default 4)",
+                None,
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Re-indent text with tabs or a different number of spaces."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"The indentation of the input is detected like str indentation detect does, and every line keeps its depth. Indentation left over after the last whole level, such as alignment spaces, is kept as spaces. Blank lines are not changed."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["indent", "tabs", "spaces", "reindent", "normalize"]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Convert four space indentation to two spaces",
+                example: r#""def f():\n    if x:\n        return 1" | str indentation convert --to spaces:2"#,
+                result: Some(Value::test_string("def f():\n  if x:\n    return 1")),
+            },
+            Example {
+                description: "Convert indentation to tabs",
+                example: r#""a:\n  b:\n    c: 1" | str indentation convert --to tabs"#,
+                result: Some(Value::test_string("a:\n\tb:\n\t\tc: 1")),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let to: Spanned<String> = call.get_flag("to")?.ok_or_else(|| {
+            LabeledError::new("Missing --to").with_label("missing flag", call.head)
+        })?;
+        let level = level(&to)?;
+        let tab_width = tab_width(call.get_flag("tab-width")?)?;
+
+        Ok(do_convert(input, &level, tab_width, call.head))
+    }
+}
+
+/// One level of the target indentation.
+fn level(to: &Spanned<String>) -> Result<String, LabeledError> {
+    let invalid = || {
+        LabeledError::new("Invalid indentation")
+            .with_label("expected tabs or spaces:<n>, like spaces:2", to.span)
+    };
+    match to.item.split_once(':') {
+        None if to.item == "tabs" => Ok("\t".to_string()),
+        None if to.item == "spaces" => Ok(" ".repeat(4)),
+        Some(("spaces", count)) => match count.trim().parse::<usize>() {
+            Ok(count) if count > 0 => Ok(" ".repeat(count)),
+            _ => Err(invalid()),
+        },
+        _ => Err(invalid()),
+    }
+}
+
+fn do_convert(input: &Value, level: &str, tab_width: usize, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => Value::string(convert(val, level, tab_width), head),
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+fn convert(text: &str, level: &str, tab_width: usize) -> String {
+    let indentation = analyze(text, tab_width);
+    text.split('\n')
+        .zip(&indentation.lines)
+        .map(|(line, indent)| match indent.style {
+            Style::Blank | Style::None => line.to_string(),
+            _ => {
+                let depth = indentation.depth(indent).unwrap_or(0);
+                let rest = indent.width - depth * indentation.unit;
+                format!(
+                    "{}{}{}",
+                    level.repeat(depth),
+                    " ".repeat(rest),
+                    &line[indent.len..]
+                )
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?
+        .test_command_examples(&StrIndentationConvert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_keeps_structure() {
+        let text = "fn f() {\n\tlet x = [\n\t\t1,\n\t  ];\n\n}";
+        assert_eq!(
+            convert(text, "  ", 4),
+            "fn f() {\n  let x = [\n    1,\n    ];\n\n}"
+        );
+        // converting back and forth gives the original text
+        let spaces = "a:\n  b:\n    c: 1\n  d: 2\n";
+        assert_eq!(convert(&convert(spaces, "\t", 4), "  ", 4), spaces);
+    }
+}
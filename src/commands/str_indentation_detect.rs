@@ -0,0 +1,295 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type,
+    Value, record,
+};
+use std::collections::HashMap;
+
+use crate::StrutilsPlugin;
+
+pub struct StrIndentationDetect;
+
+impl SimplePluginCommand for StrIndentationDetect {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str indentation detect"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![(Type::String, Type::record())])
+            .named(
+                "tab-width",
+                SyntaxShape::Int,
+                "Columns a tab counts for when measuring indentation. (default 4)",
+                None,
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Detect the indentation style, unit and per-line depth of text."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"The style is whichever of tabs or spaces indents more lines, and the unit is the most common step between the indentation of consecutive lines. Lines that mix tabs and spaces, or use the other style, are listed as mixed.
+
+Each row of the lines table has the 1-based line number, the indentation width in columns, the depth in units, and the style of that line: none, spaces, tabs, mixed or blank."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["indent", "tabs", "spaces", "lint", "whitespace"]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            description: "Detect two space indentation",
+            example: r#""a:\n  b:\n    c: 1\n\td: 2" | str indentation detect"#,
+            result: Some(Value::test_record(record! {
+                "style" => Value::test_string("spaces"),
+                "unit" => Value::test_int(2),
+                "mixed" => Value::test_list(vec![Value::test_int(4)]),
+                "lines" => Value::test_list(vec![
+                    line_record(1, 0, Some(0), "none"),
+                    line_record(2, 2, Some(1), "spaces"),
+                    line_record(3, 4, Some(2), "spaces"),
+                    line_record(4, 4, Some(2), "tabs"),
+                ]),
+            })),
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let tab_width = tab_width(call.get_flag("tab-width")?)?;
+
+        Ok(do_detect(input, tab_width, call.head))
+    }
+}
+
+/// Validate a `--tab-width` flag, which defaults to 4.
+pub(crate) fn tab_width(width: Option<Spanned<i64>>) -> Result<usize, LabeledError> {
+    match width {
+        Some(width) if width.item < 1 => Err(LabeledError::new("Invalid tab width")
+            .with_label("expected a positive number", width.span)),
+        Some(width) => Ok(width.item as usize),
+        None => Ok(4),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Style {
+    None,
+    Spaces,
+    Tabs,
+    Mixed,
+    Blank,
+}
+
+impl Style {
+    fn name(self) -> &'static str {
+        match self {
+            Style::None => "none",
+            Style::Spaces => "spaces",
+            Style::Tabs => "tabs",
+            Style::Mixed => "mixed",
+            Style::Blank => "blank",
+        }
+    }
+}
+
+pub(crate) struct LineIndent {
+    pub(crate) style: Style,
+    /// Width of the indentation in columns, with tabs expanded to the next tab stop.
+    pub(crate) width: usize,
+    /// Byte length of the indentation.
+    pub(crate) len: usize,
+}
+
+pub(crate) struct Indentation {
+    /// The style used by most indented lines: spaces, tabs or none.
+    pub(crate) style: Style,
+    /// Columns per level of indentation.
+    pub(crate) unit: usize,
+    pub(crate) lines: Vec<LineIndent>,
+}
+
+impl Indentation {
+    pub(crate) fn depth(&self, line: &LineIndent) -> Option<usize> {
+        match line.style {
+            Style::Blank => None,
+            _ if self.unit == 0 => Some(0),
+            _ => Some(line.width / self.unit),
+        }
+    }
+
+    /// Whether a line is indented differently from the text as a whole.
+    fn is_mixed(&self, line: &LineIndent) -> bool {
+        match line.style {
+            Style::Mixed => true,
+            Style::Spaces | Style::Tabs => line.style != self.style,
+            _ => false,
+        }
+    }
+}
+
+/// Measure the indentation of every line of `text`, split on `\n`.
+pub(crate) fn analyze(text: &str, tab_width: usize) -> Indentation {
+    let lines: Vec<LineIndent> = text
+        .split('\n')
+        .map(|line| {
+            let content = line.trim_start_matches([' ', '\t']);
+            let indent = &line[..line.len() - content.len()];
+            let width = indent.chars().fold(0, |column, c| match c {
+                '\t' => column + tab_width - column % tab_width,
+                _ => column + 1,
+            });
+            let style = match (indent.contains(' '), indent.contains('\t')) {
+                _ if content.trim().is_empty() => Style::Blank,
+                (false, false) => Style::None,
+                (true, false) => Style::Spaces,
+                (false, true) => Style::Tabs,
+                (true, true) => Style::Mixed,
+            };
+            LineIndent {
+                style,
+                width,
+                len: indent.len(),
+            }
+        })
+        .collect();
+
+    let count = |style| lines.iter().filter(|line| line.style == style).count();
+    let style = match (count(Style::Spaces), count(Style::Tabs)) {
+        (0, 0) => Style::None,
+        (spaces, tabs) if tabs > spaces => Style::Tabs,
+        _ => Style::Spaces,
+    };
+
+    let unit = match style {
+        Style::None => 0,
+        Style::Tabs => tab_width,
+        _ => {
+            // the most common step up between consecutive lines, preferring the smaller one
+            let mut steps: HashMap<usize, usize> = HashMap::new();
+            let mut previous = 0;
+            for line in lines.iter().filter(|line| line.style != Style::Blank) {
+                if line.width > previous {
+                    *steps.entry(line.width - previous).or_default() += 1;
+                }
+                previous = line.width;
+            }
+            steps
+                .into_iter()
+                .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))
+                .map_or(0, |(step, _)| step)
+        }
+    };
+
+    Indentation { style, unit, lines }
+}
+
+fn do_detect(input: &Value, tab_width: usize, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => {
+            let indentation = analyze(val, tab_width);
+            let mixed = (1..)
+                .zip(&indentation.lines)
+                .filter(|(_, line)| indentation.is_mixed(line))
+                .map(|(number, _)| Value::int(number, head))
+                .collect();
+            let lines = (1..)
+                .zip(&indentation.lines)
+                .map(|(number, line)| {
+                    Value::record(
+                        record! {
+                            "line" => Value::int(number, head),
+                            "width" => Value::int(line.width as i64, head),
+                            "depth" => match indentation.depth(line) {
+                                Some(depth) => Value::int(depth as i64, head),
+                                None => Value::nothing(head),
+                            },
+                            "style" => Value::string(line.style.name(), head),
+                        },
+                        head,
+                    )
+                })
+                .collect();
+
+            Value::record(
+                record! {
+                    "style" => Value::string(indentation.style.name(), head),
+                    "unit" => Value::int(indentation.unit as i64, head),
+                    "mixed" => Value::list(mixed, head),
+                    "lines" => Value::list(lines, head),
+                },
+                head,
+            )
+        }
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+/// A row of the lines table, for the examples.
+fn line_record(line: i64, width: i64, depth: Option<i64>, style: &str) -> Value {
+    Value::test_record(record! {
+        "line" => Value::test_int(line),
+        "width" => Value::test_int(width),
+        "depth" => depth.map_or(Value::test_nothing(), Value::test_int),
+        "style" => Value::test_string(style),
+    })
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrIndentationDetect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> (&'static str, usize) {
+        let indentation = analyze(text, 4);
+        (indentation.style.name(), indentation.unit)
+    }
+
+    #[test]
+    fn test_detect_style_and_unit() {
+        assert_eq!(
+            detect("def f():\n    if x:\n        y\n    z"),
+            ("spaces", 4)
+        );
+        assert_eq!(detect("a:\n  - b\n  - c:\n      d: 1"), ("spaces", 2));
+        assert_eq!(detect("fn f() {\n\tif x {\n\t\ty\n\t}\n}"), ("tabs", 4));
+        assert_eq!(detect("no\nindent\n\n"), ("none", 0));
+    }
+
+    #[test]
+    fn test_blank_lines_have_no_depth() {
+        let indentation = analyze("a\n    \n  b", 4);
+        let depths: Vec<_> = indentation
+            .lines
+            .iter()
+            .map(|line| indentation.depth(line))
+            .collect();
+        assert_eq!(depths, [Some(0), None, Some(1)]);
+    }
+}
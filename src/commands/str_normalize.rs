@@ -0,0 +1,229 @@
+use super::operate::operate;
+use caseless::default_case_fold_str;
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::ast::CellPath;
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type,
+    Value, record,
+};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::StrutilsPlugin;
+
+pub struct StrNormalize;
+
+impl SimplePluginCommand for StrNormalize {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str normalize"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::String, Type::String),
+                (Type::String, Type::Bool),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::String)),
+                ),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::Bool)),
+                ),
+                (Type::table(), Type::table()),
+                (Type::record(), Type::record()),
+            ])
+            .allow_variants_without_examples(true)
+            .rest(
+                "rest",
+                SyntaxShape::CellPath,
+                "For a data structure input, normalize the strings at the given cell paths.",
+            )
+            .named(
+                "form",
+                SyntaxShape::String,
+                "Normalization form: nfc, nfd, nfkc or nfkd. (default nfc)",
+                Some('f'),
+            )
+            .switch(
+                "casefold",
+                "Apply full Unicode case folding before normalizing.",
+                Some('c'),
+            )
+            .switch(
+                "check",
+                "Return whether the input is already normalized instead of normalizing it.",
+                None,
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Normalize Unicode strings to a normalization form."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"Unlike str deunicode, normalization is lossless for the canonical forms: NFC composes characters and NFD decomposes them into base characters and combining marks. The compatibility forms NFKC and NFKD also replace compatibility characters such as ligatures and circled digits with their plain equivalents.
+
+With --casefold, strings are decomposed, case folded and decomposed again before the requested form is applied, as in Unicode canonical caseless matching, so text that only differs in case or normalization compares equal."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec![
+            "unicode",
+            "nfc",
+            "nfd",
+            "nfkc",
+            "nfkd",
+            "casefold",
+            "compose",
+            "decompose",
+        ]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Compose a base character and a combining accent",
+                example: r#""e\u{301}" | str normalize"#,
+                result: Some(Value::test_string("é")),
+            },
+            Example {
+                description: "Decompose characters into base characters and combining marks",
+                example: r#""é" | str normalize --form nfd"#,
+                result: Some(Value::test_string("e\u{301}")),
+            },
+            Example {
+                description: "Replace compatibility characters",
+                example: r#""ﬁle ①" | str normalize --form nfkc"#,
+                result: Some(Value::test_string("file 1")),
+            },
+            Example {
+                description: "Case fold and normalize before comparing",
+                example: r#""Straße" | str normalize --casefold"#,
+                result: Some(Value::test_string("strasse")),
+            },
+            Example {
+                description: "Check whether strings are already normalized",
+                example: r#"["é" "e\u{301}"] | str normalize --check"#,
+                result: Some(Value::test_list(vec![
+                    Value::test_bool(true),
+                    Value::test_bool(false),
+                ])),
+            },
+            Example {
+                description: "Normalize a column of a table",
+                example: r#"[[name]; ["Cafe\u{301}"]] | str normalize name"#,
+                result: Some(Value::test_list(vec![Value::test_record(record! {
+                    "name" => Value::test_string("Café"),
+                })])),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let paths: Vec<CellPath> = call.rest(0)?;
+        let form = match call.get_flag("form")? {
+            Some(form) => Form::from_name(form)?,
+            None => Form::Nfc,
+        };
+        let casefold = call.has_flag("casefold")?;
+        let check = call.has_flag("check")?;
+        let head = call.head;
+
+        Ok(operate(input, &paths, head, &|value| {
+            do_normalize(value, form, casefold, check, head)
+        }))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Form {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl Form {
+    fn from_name(name: Spanned<String>) -> Result<Self, LabeledError> {
+        match name.item.to_lowercase().as_str() {
+            "nfc" => Ok(Form::Nfc),
+            "nfd" => Ok(Form::Nfd),
+            "nfkc" => Ok(Form::Nfkc),
+            "nfkd" => Ok(Form::Nfkd),
+            _ => Err(LabeledError::new("Invalid normalization form")
+                .with_label("expected nfc, nfd, nfkc or nfkd", name.span)),
+        }
+    }
+}
+
+fn do_normalize(input: &Value, form: Form, casefold: bool, check: bool, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } if check => {
+            Value::bool(normalize(val, form, casefold) == *val, head)
+        }
+        Value::String { val, .. } => Value::string(normalize(val, form, casefold), head),
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+fn normalize(text: &str, form: Form, casefold: bool) -> String {
+    let folded;
+    let text = if casefold {
+        // canonical caseless matching: decompose first so that combining marks folded to
+        // letters, like the ypogegrammeni, are in canonical order, and again after folding
+        folded = default_case_fold_str(&text.nfd().collect::<String>())
+            .nfd()
+            .collect::<String>();
+        &folded
+    } else {
+        text
+    };
+
+    match form {
+        Form::Nfc => text.nfc().collect(),
+        Form::Nfd => text.nfd().collect(),
+        Form::Nfkc => text.nfkc().collect(),
+        Form::Nfkd => text.nfkd().collect(),
+    }
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrNormalize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_casefold_canonical_order() {
+        // the ypogegrammeni folds to iota, which only gets the accent if folded in NFD order
+        let folded = normalize("\u{3b1}\u{345}\u{301}", Form::Nfc, true);
+        assert_eq!(folded, normalize("\u{3b1}\u{301}\u{345}", Form::Nfc, true));
+        assert_eq!(folded, "\u{3ac}\u{3b9}");
+        assert_eq!(normalize("\u{1fb4}", Form::Nfc, true), "\u{3ac}\u{3b9}");
+        assert_eq!(normalize("Å", Form::Nfd, true), "a\u{30a}");
+    }
+}
@@ -0,0 +1,199 @@
+use super::operate::operate;
+use super::str_wrap::{resolve_width, terminal_width};
+use super::width::{map_lines, text_width};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::ast::CellPath;
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type, Value,
+};
+
+use crate::StrutilsPlugin;
+
+pub struct StrPad;
+
+impl SimplePluginCommand for StrPad {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str pad"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::String, Type::String),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::String)),
+                ),
+                (Type::table(), Type::table()),
+                (Type::record(), Type::record()),
+            ])
+            .allow_variants_without_examples(true)
+            .rest(
+                "rest",
+                SyntaxShape::CellPath,
+                "For a data structure input, pad the strings at the given cell paths.",
+            )
+            .required_named(
+                "width",
+                SyntaxShape::OneOf(vec![SyntaxShape::Int, SyntaxShape::String]),
+                "Width in columns to pad to, an offset from the terminal width like -4, or a percentage of it like 50%.",
+                Some('w'),
+            )
+            .named(
+                "align",
+                SyntaxShape::String,
+                "Where to put the text: left, right or center. (default left)",
+                Some('a'),
+            )
+            .named(
+                "character",
+                SyntaxShape::String,
+                "Character to pad with, which must be one column wide. (default space)",
+                Some('c'),
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Pad strings to a display width."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"Unlike fill, the width is measured like str width, so strings with wide characters, emoji or ANSI escape sequences line up in a terminal. Strings that are already wide enough are left as they are, and each line of text with several lines is padded on its own. When centering, the extra column goes to the right."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["fill", "align", "justify", "center", "width", "columns"]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Pad wide characters to 6 columns",
+                example: r#""日本" | str pad --width 6"#,
+                result: Some(Value::test_string("日本  ")),
+            },
+            Example {
+                description: "Align emoji to the right",
+                example: r#"["a" "👍🏽"] | str pad --width 3 --align right"#,
+                result: Some(Value::test_list(vec![
+                    Value::test_string("  a"),
+                    Value::test_string(" 👍🏽"),
+                ])),
+            },
+            Example {
+                description: "Center a title with dashes",
+                example: r#""title" | str pad --width 11 --align center --character "-""#,
+                result: Some(Value::test_string("---title---")),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let paths: Vec<CellPath> = call.rest(0)?;
+        let width: Value = call.get_flag("width")?.ok_or_else(|| {
+            LabeledError::new("Missing --width").with_label("missing flag", call.head)
+        })?;
+        let width = resolve_width(&width, || terminal_width(engine, call.head))?;
+        let align = match call.get_flag("align")? {
+            Some(align) => Align::from_name(align)?,
+            None => Align::Left,
+        };
+        let character = match call.get_flag::<Spanned<String>>("character")? {
+            Some(character) if text_width(&character.item) != 1 => {
+                return Err(LabeledError::new("Invalid padding character")
+                    .with_label("expected a character one column wide", character.span));
+            }
+            Some(character) => character.item,
+            None => " ".to_string(),
+        };
+        let head = call.head;
+
+        Ok(operate(input, &paths, head, &|value| {
+            do_pad(value, width, align, &character, head)
+        }))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+impl Align {
+    fn from_name(name: Spanned<String>) -> Result<Self, LabeledError> {
+        match name.item.as_str() {
+            "left" => Ok(Align::Left),
+            "right" => Ok(Align::Right),
+            "center" => Ok(Align::Center),
+            _ => Err(LabeledError::new("Invalid alignment")
+                .with_label("expected left, right or center", name.span)),
+        }
+    }
+}
+
+fn do_pad(input: &Value, width: usize, align: Align, character: &str, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => Value::string(
+            map_lines(val, |line| pad(line, width, align, character)),
+            head,
+        ),
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+fn pad(line: &str, width: usize, align: Align, character: &str) -> String {
+    let padding = width.saturating_sub(text_width(line));
+    let left = match align {
+        Align::Left => 0,
+        Align::Right => padding,
+        Align::Center => padding / 2,
+    };
+
+    [
+        character.repeat(left),
+        line.to_string(),
+        character.repeat(padding - left),
+    ]
+    .concat()
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrPad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_lines() {
+        let pad_lines = |text| map_lines(text, |line| pad(line, 4, Align::Center, "."));
+        assert_eq!(pad_lines(""), "....");
+        assert_eq!(pad_lines("a\r\nbc\n"), ".a..\r\n.bc.\n");
+        assert_eq!(pad_lines("\x1b[1m日\x1b[0m"), ".\x1b[1m日\x1b[0m.");
+        assert_eq!(pad_lines("wider"), "wider");
+    }
+}
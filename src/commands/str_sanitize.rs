@@ -0,0 +1,393 @@
+use super::operate::operate;
+use super::str_chars::{char_name, is_noncharacter};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::ast::CellPath;
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type,
+    Value, record,
+};
+use unicode_general_category::{GeneralCategory, get_general_category};
+use unicode_normalization::char::canonical_combining_class;
+use unicode_script::{Script, UnicodeScript};
+use unicode_segmentation::GraphemeCursor;
+
+use crate::StrutilsPlugin;
+
+pub struct StrSanitize;
+
+impl SimplePluginCommand for StrSanitize {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str sanitize"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::String, Type::String),
+                (Type::String, Type::table()),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::String)),
+                ),
+                (Type::table(), Type::table()),
+                (Type::record(), Type::record()),
+            ])
+            .allow_variants_without_examples(true)
+            .rest(
+                "rest",
+                SyntaxShape::CellPath,
+                "For a data structure input, sanitize the strings at the given cell paths.",
+            )
+            .named(
+                "classes",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "Classes of characters to handle: bidi, zero-width, private-use, unassigned, control and noncharacter. (default all)",
+                Some('c'),
+            )
+            .named(
+                "replace",
+                SyntaxShape::String,
+                "Replace each character found with this string instead of removing it.",
+                Some('r'),
+            )
+            .switch(
+                "report",
+                "Return a table of the characters found and their positions instead.",
+                None,
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Find, remove or replace invisible and dangerous characters."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"The classes are:
+  bidi: bidirectional formatting characters used in Trojan Source attacks, such as U+202E
+  zero-width: zero width spaces and joiners, the word joiner and the byte order mark. The joiners U+200C and U+200D are left alone where they join something: after a virama, between letters of a script with joining forms such as Arabic, and inside emoji sequences
+  private-use: characters from the private use areas
+  unassigned: code points that are not assigned in Unicode
+  control: C0 and C1 control characters and DEL, except tab, line feed and carriage return
+  noncharacter: the 66 code points reserved as noncharacters, such as U+FFFF
+
+Each row of the report has the byte offset, the 1-based line and column in characters, the character, its code point, name and class."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec![
+            "invisible",
+            "zero-width",
+            "bidi",
+            "trojan",
+            "bom",
+            "control",
+            "clean",
+            "strip",
+        ]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Remove zero width spaces and a byte order mark",
+                example: r#""\u{feff}key\u{200b} = value" | str sanitize"#,
+                result: Some(Value::test_string("key = value")),
+            },
+            Example {
+                description: "Report bidi controls with their positions",
+                example: r#""ok\nif x {\u{202e} }" | str sanitize --report"#,
+                result: Some(Value::test_list(vec![Value::test_record(record! {
+                    "offset" => Value::test_int(9),
+                    "line" => Value::test_int(2),
+                    "column" => Value::test_int(7),
+                    "char" => Value::test_string("\u{202e}"),
+                    "codepoint" => Value::test_string("U+202E"),
+                    "name" => Value::test_string("RIGHT-TO-LEFT OVERRIDE"),
+                    "class" => Value::test_string("bidi"),
+                })])),
+            },
+            Example {
+                description: "Replace control characters and keep everything else",
+                example: r#""a\u{7}b\u{200b}c" | str sanitize --classes [control] --replace "\u{fffd}""#,
+                result: Some(Value::test_string("a\u{fffd}b\u{200b}c")),
+            },
+            Example {
+                description: "Keep joiners that are part of an emoji or Persian text",
+                example: r#""👨\u{200d}👩\u{200d}👧 می\u{200c}خواهم" | str sanitize"#,
+                result: Some(Value::test_string("👨\u{200d}👩\u{200d}👧 می\u{200c}خواهم")),
+            },
+            Example {
+                description: "Sanitize a column of a table",
+                example: r#"[[key]; ["na\u{200b}me"]] | str sanitize key"#,
+                result: Some(Value::test_list(vec![Value::test_record(record! {
+                    "key" => Value::test_string("name"),
+                })])),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let paths: Vec<CellPath> = call.rest(0)?;
+        let classes = match call.get_flag::<Vec<Spanned<String>>>("classes")? {
+            Some(names) => names
+                .iter()
+                .map(Class::from_name)
+                .collect::<Result<_, _>>()?,
+            None => Class::ALL.to_vec(),
+        };
+        let replace: Option<String> = call.get_flag("replace")?;
+        let report = call.has_flag("report")?;
+        if report && replace.is_some() {
+            return Err(
+                LabeledError::new("Only one of --report and --replace can be used")
+                    .with_label("conflicting flags", call.head),
+            );
+        }
+        let action = match replace {
+            _ if report => Action::Report,
+            Some(replacement) => Action::Replace(replacement),
+            None => Action::Replace(String::new()),
+        };
+        let head = call.head;
+
+        Ok(operate(input, &paths, head, &|value| {
+            do_sanitize(value, &classes, &action, head)
+        }))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Class {
+    Bidi,
+    ZeroWidth,
+    PrivateUse,
+    Unassigned,
+    Control,
+    Noncharacter,
+}
+
+impl Class {
+    const ALL: [Class; 6] = [
+        Class::Bidi,
+        Class::ZeroWidth,
+        Class::PrivateUse,
+        Class::Unassigned,
+        Class::Control,
+        Class::Noncharacter,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Class::Bidi => "bidi",
+            Class::ZeroWidth => "zero-width",
+            Class::PrivateUse => "private-use",
+            Class::Unassigned => "unassigned",
+            Class::Control => "control",
+            Class::Noncharacter => "noncharacter",
+        }
+    }
+
+    fn from_name(name: &Spanned<String>) -> Result<Self, LabeledError> {
+        Class::ALL
+            .into_iter()
+            .find(|class| class.name() == name.item)
+            .ok_or_else(|| {
+                LabeledError::new("Unknown character class").with_label(
+                    "expected bidi, zero-width, private-use, unassigned, control or noncharacter",
+                    name.span,
+                )
+            })
+    }
+
+    /// The class of `c`, if it is in one.
+    fn of(c: char) -> Option<Self> {
+        match c {
+            '\u{061C}'
+            | '\u{200E}'
+            | '\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2066}'..='\u{2069}' => Some(Class::Bidi),
+            '\u{180E}' | '\u{200B}'..='\u{200D}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}' => {
+                Some(Class::ZeroWidth)
+            }
+            '\t' | '\n' | '\r' => None,
+            _ if is_noncharacter(c) => Some(Class::Noncharacter),
+            _ => match get_general_category(c) {
+                GeneralCategory::Control => Some(Class::Control),
+                GeneralCategory::PrivateUse => Some(Class::PrivateUse),
+                GeneralCategory::Unassigned => Some(Class::Unassigned),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Whether the joiner at byte `i` of `text` joins its neighbours, so it is needed for the text to
+/// render correctly.
+fn joins(text: &str, i: usize, joiner: char) -> bool {
+    let previous = text[..i].chars().next_back();
+    let end = i + joiner.len_utf8();
+    let next = text[end..].chars().next();
+    let joining = |c: Option<char>| {
+        c.filter(|c| c.is_alphabetic()).is_some_and(|c| {
+            matches!(
+                c.script(),
+                Script::Arabic
+                    | Script::Syriac
+                    | Script::Nko
+                    | Script::Mongolian
+                    | Script::Mandaic
+                    | Script::Manichaean
+                    | Script::Psalter_Pahlavi
+                    | Script::Adlam
+                    | Script::Hanifi_Rohingya
+                    | Script::Sogdian
+                    | Script::Old_Uyghur
+                    | Script::Phags_Pa
+            )
+        })
+    };
+    // emoji sequences are kept in one grapheme cluster across the zero width joiner
+    let in_cluster = || {
+        joiner == '\u{200D}'
+            && next.is_some()
+            && !GraphemeCursor::new(end, text.len(), true)
+                .is_boundary(text, 0)
+                .unwrap_or(true)
+    };
+
+    previous.is_some_and(|c| canonical_combining_class(c) == 9)
+        || (joining(previous) && joining(next))
+        || in_cluster()
+}
+
+enum Action {
+    Replace(String),
+    Report,
+}
+
+fn do_sanitize(input: &Value, classes: &[Class], action: &Action, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => {
+            let found = |i, c| {
+                let class = Class::of(c).filter(|class| classes.contains(class))?;
+                match c {
+                    '\u{200C}' | '\u{200D}' if joins(val, i, c) => None,
+                    _ => Some(class),
+                }
+            };
+            match action {
+                Action::Replace(replacement) => {
+                    let mut result = String::with_capacity(val.len());
+                    for (i, c) in val.char_indices() {
+                        match found(i, c) {
+                            Some(_) => result.push_str(replacement),
+                            None => result.push(c),
+                        }
+                    }
+                    Value::string(result, head)
+                }
+                Action::Report => {
+                    let mut rows = vec![];
+                    let (mut line, mut column) = (1, 1);
+                    for (offset, c) in val.char_indices() {
+                        if let Some(class) = found(offset, c) {
+                            rows.push(Value::record(
+                                record! {
+                                    "offset" => Value::int(offset as i64, head),
+                                    "line" => Value::int(line, head),
+                                    "column" => Value::int(column, head),
+                                    "char" => Value::string(c, head),
+                                    "codepoint" => Value::string(format!("U+{:04X}", c as u32), head),
+                                    "name" => Value::string(char_name(c), head),
+                                    "class" => Value::string(class.name(), head),
+                                },
+                                head,
+                            ));
+                        }
+                        if c == '\n' {
+                            (line, column) = (line + 1, 1);
+                        } else {
+                            column += 1;
+                        }
+                    }
+                    Value::list(rows, head)
+                }
+            }
+        }
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrSanitize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classes() {
+        let class = |c| Class::of(c).map(Class::name);
+        assert_eq!(class('\u{202E}'), Some("bidi"));
+        assert_eq!(class('\u{2067}'), Some("bidi"));
+        assert_eq!(class('\u{FEFF}'), Some("zero-width"));
+        assert_eq!(class('\u{E000}'), Some("private-use"));
+        assert_eq!(class('\u{378}'), Some("unassigned"));
+        assert_eq!(class('\u{85}'), Some("control"));
+        assert_eq!(class('\u{7F}'), Some("control"));
+        assert_eq!(class('\u{FDD0}'), Some("noncharacter"));
+        assert_eq!(class('\u{10FFFF}'), Some("noncharacter"));
+        for c in ['\t', '\n', '\r', 'a', ' ', '\u{a0}', '\u{ad}', '😀'] {
+            assert_eq!(class(c), None);
+        }
+    }
+
+    #[test]
+    fn test_joiners() {
+        let sanitize = |text| {
+            let value = do_sanitize(
+                &Value::test_string(text),
+                &Class::ALL,
+                &Action::Replace(String::new()),
+                Span::test_data(),
+            );
+            value.into_string().unwrap()
+        };
+        for text in [
+            "👨\u{200d}👩\u{200d}👧",
+            "🏳\u{fe0f}\u{200d}🌈",
+            "می\u{200c}خواهم",
+            "क्\u{200d}ष",
+            "क्\u{200c}ष",
+        ] {
+            assert_eq!(sanitize(text), text);
+        }
+        assert_eq!(sanitize("na\u{200d}me"), "name");
+        assert_eq!(sanitize("a\u{200c}b"), "ab");
+        assert_eq!(sanitize("\u{200d}👩"), "👩");
+        assert_eq!(sanitize("👩\u{200d}"), "👩");
+    }
+}
@@ -4,7 +4,40 @@ use nu_protocol::{
     Category, Example, LabeledError, Signature, Span, Spanned, SyntaxShape, Value, record,
 };
 use std::vec;
-use textdistance::{nstr, str};
+use textdistance::{
+    Algorithm, Bag, Cosine, DamerauLevenshtein, EntropyNCD, Hamming, Jaccard, Jaro, JaroWinkler,
+    LCSSeq, LCSStr, LIG3, Length, Levenshtein, MLIPNS, Overlap, Prefix, RatcliffObershelp, Roberts,
+    Sift4Common, Sift4Simple, SmithWaterman, SorensenDice, Suffix, Tversky, YujianBo, nstr, str,
+};
+
+/// Every supported algorithm with its short alias, in the order they are listed and computed.
+pub(crate) const ALGORITHMS: [(&str, &str); 25] = [
+    ("bag", "bag"),
+    ("cosine", "cos"),
+    ("damerau_levenshtein", "dlev"),
+    ("entropy_ncd", "entncd"),
+    ("hamming", "ham"),
+    ("jaccard", "jac"),
+    ("jaro", "jar"),
+    ("jaro_winkler", "jarw"),
+    ("levenshtein", "lev"),
+    ("longest_common_subsequence", "lcsubseq"),
+    ("longest_common_substring", "lcsubstr"),
+    ("length", "len"),
+    ("lig3", "lig"),
+    ("mlipns", "mli"),
+    ("overlap", "olap"),
+    ("prefix", "pre"),
+    ("ratcliff_obershelp", "rat"),
+    ("roberts", "rob"),
+    ("sift4_common", "scom"),
+    ("sift4_simple", "ssim"),
+    ("smith_waterman", "smithw"),
+    ("sorensen_dice", "soredice"),
+    ("suffix", "suf"),
+    ("tversky", "tv"),
+    ("yujian_bo", "ybo"),
+];
 
 pub struct StrSimilarity;
 
@@ -123,35 +156,8 @@ impl SimplePluginCommand for StrSimilarity {
 
 fn compute_all(s1: &str, s2: &str, norm: bool) -> Result<Value, LabeledError> {
     let span = Span::unknown();
-    let algos = vec![
-        "bag",
-        "cosine",
-        "damerau_levenshtein",
-        "entropy_ncd",
-        "hamming",
-        "jaccard",
-        "jaro",
-        "jaro_winkler",
-        "levenshtein",
-        "longest_common_subsequence",
-        "longest_common_substring",
-        "length",
-        "lig3",
-        "mlipns",
-        "overlap",
-        "prefix",
-        "ratcliff_obershelp",
-        "roberts",
-        "sift4_common",
-        "sift4_simple",
-        "smith_waterman",
-        "sorensen_dice",
-        "suffix",
-        "tversky",
-        "yujian_bo",
-    ];
     let mut rows = vec![];
-    for algo in algos {
+    for (algo, _) in ALGORITHMS {
         let sim = Value::string(algo.to_string(), span);
         let val_comp = compute(algo, s1, s2, norm);
         let val = if val_comp.fract() == 0.0 {
@@ -169,37 +175,76 @@ fn compute_all(s1: &str, s2: &str, norm: bool) -> Result<Value, LabeledError> {
 
 #[rustfmt::skip]
 fn compute(a: &str, s1: &str, s2: &str, norm: bool) -> f64 {
-    let sim = a.to_lowercase();
-    match sim.as_str() {
+    match algorithm_name(a).unwrap_or("levenshtein") {
         "bag" => if norm { nstr::bag(s1, s2) } else {str::bag(s1, s2) as f64},
-        "cos" | "cosine" => if norm { nstr::cosine(s1, s2) } else {str::cosine(s1, s2)},
-        "dlev" | "damerau_levenshtein" => if norm { nstr::damerau_levenshtein(s1, s2) } else {str::damerau_levenshtein(s1, s2) as f64},
-        "entncd" | "entropy_ncd" => if norm { nstr::entropy_ncd(s1, s2) } else {str::entropy_ncd(s1, s2)},
-        "ham" | "hamming" => if norm { nstr::hamming(s1, s2) } else {str::hamming(s1, s2) as f64},
-        "jac" | "jaccard" => if norm { nstr::jaccard(s1, s2) } else {str::jaccard(s1, s2)},
-        "jar" | "jaro" => if norm { nstr::jaro(s1, s2) } else {str::jaro(s1, s2)},
-        "jarw" | "jaro_winkler" => if norm { nstr::jaro_winkler(s1, s2) } else {str::jaro_winkler(s1, s2)},
-        "lev" | "levenshtein" => if norm { nstr::levenshtein(s1, s2) } else {str::levenshtein(s1, s2) as f64},
-        "lcsubseq" | "longest_common_subsequence" => if norm { nstr::lcsseq(s1, s2) } else {str::lcsseq(s1, s2) as f64},
-        "lcsubstr" | "longest_common_substring" => if norm { nstr::lcsstr(s1, s2) } else {str::lcsstr(s1, s2) as f64},
-        "len" | "length" => if norm { nstr::length(s1, s2) } else {str::length(s1, s2) as f64},
-        "lig" | "lig3" => if norm { nstr::lig3(s1, s2) } else {str::lig3(s1, s2)},
-        "mli" | "mlipns" => if norm { nstr::mlipns(s1, s2) } else {str::mlipns(s1, s2) as f64},
-        "olap" | "overlap" => if norm { nstr::overlap(s1, s2) } else {str::overlap(s1, s2)},
-        "pre" | "prefix" => if norm { nstr::prefix(s1, s2) } else {str::prefix(s1, s2) as f64},
-        "rat" | "ratcliff_obershelp" => if norm { nstr::ratcliff_obershelp(s1, s2) } else {str::ratcliff_obershelp(s1, s2)},
-        "rob" | "roberts" => if norm { nstr::roberts(s1, s2) } else {str::roberts(s1, s2)},
-        "scom" | "sift4_common" => if norm { nstr::sift4_common(s1, s2) } else {str::sift4_common(s1, s2) as f64},
-        "ssim" | "sift4_simple" => if norm { nstr::sift4_simple(s1, s2) } else {str::sift4_simple(s1, s2) as f64},
-        "smithw" | "smith_waterman" => if norm { nstr::smith_waterman(s1, s2) } else {str::smith_waterman(s1, s2) as f64},
-        "soredice" | "sorensen_dice" => if norm { nstr::sorensen_dice(s1, s2) } else {str::sorensen_dice(s1, s2)},
-        "suf" | "suffix" => if norm { nstr::suffix(s1, s2) } else {str::suffix(s1, s2) as f64},
-        "tv" | "tversky" => if norm { nstr::tversky(s1, s2) } else {str::tversky(s1, s2)},
-        "ybo" | "yujian_bo" => if norm { nstr::yujian_bo(s1, s2) } else {str::yujian_bo(s1, s2)},
+        "cosine" => if norm { nstr::cosine(s1, s2) } else {str::cosine(s1, s2)},
+        "damerau_levenshtein" => if norm { nstr::damerau_levenshtein(s1, s2) } else {str::damerau_levenshtein(s1, s2) as f64},
+        "entropy_ncd" => if norm { nstr::entropy_ncd(s1, s2) } else {str::entropy_ncd(s1, s2)},
+        "hamming" => if norm { nstr::hamming(s1, s2) } else {str::hamming(s1, s2) as f64},
+        "jaccard" => if norm { nstr::jaccard(s1, s2) } else {str::jaccard(s1, s2)},
+        "jaro" => if norm { nstr::jaro(s1, s2) } else {str::jaro(s1, s2)},
+        "jaro_winkler" => if norm { nstr::jaro_winkler(s1, s2) } else {str::jaro_winkler(s1, s2)},
+        "longest_common_subsequence" => if norm { nstr::lcsseq(s1, s2) } else {str::lcsseq(s1, s2) as f64},
+        "longest_common_substring" => if norm { nstr::lcsstr(s1, s2) } else {str::lcsstr(s1, s2) as f64},
+        "length" => if norm { nstr::length(s1, s2) } else {str::length(s1, s2) as f64},
+        "lig3" => if norm { nstr::lig3(s1, s2) } else {str::lig3(s1, s2)},
+        "mlipns" => if norm { nstr::mlipns(s1, s2) } else {str::mlipns(s1, s2) as f64},
+        "overlap" => if norm { nstr::overlap(s1, s2) } else {str::overlap(s1, s2)},
+        "prefix" => if norm { nstr::prefix(s1, s2) } else {str::prefix(s1, s2) as f64},
+        "ratcliff_obershelp" => if norm { nstr::ratcliff_obershelp(s1, s2) } else {str::ratcliff_obershelp(s1, s2)},
+        "roberts" => if norm { nstr::roberts(s1, s2) } else {str::roberts(s1, s2)},
+        "sift4_common" => if norm { nstr::sift4_common(s1, s2) } else {str::sift4_common(s1, s2) as f64},
+        "sift4_simple" => if norm { nstr::sift4_simple(s1, s2) } else {str::sift4_simple(s1, s2) as f64},
+        "smith_waterman" => if norm { nstr::smith_waterman(s1, s2) } else {str::smith_waterman(s1, s2) as f64},
+        "sorensen_dice" => if norm { nstr::sorensen_dice(s1, s2) } else {str::sorensen_dice(s1, s2)},
+        "suffix" => if norm { nstr::suffix(s1, s2) } else {str::suffix(s1, s2) as f64},
+        "tversky" => if norm { nstr::tversky(s1, s2) } else {str::tversky(s1, s2)},
+        "yujian_bo" => if norm { nstr::yujian_bo(s1, s2) } else {str::yujian_bo(s1, s2)},
         _ => if norm { nstr::levenshtein(s1, s2) } else {str::levenshtein(s1, s2) as f64},
     }
 }
 
+/// Resolve an algorithm name or alias (case-insensitive) to its full name.
+pub(crate) fn algorithm_name(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    ALGORITHMS
+        .iter()
+        .find(|(algo, short)| *algo == name || *short == name)
+        .map(|(algo, _)| *algo)
+}
+
+/// Normalized distance between 0 (identical) and 1 (completely different). Unlike `compute`,
+/// similarity based algorithms are inverted so every algorithm can be ranked the same way.
+pub(crate) fn normalized_distance(a: &str, s1: &str, s2: &str) -> f64 {
+    match algorithm_name(a).unwrap_or("levenshtein") {
+        "bag" => Bag::default().for_str(s1, s2).ndist(),
+        "cosine" => Cosine::default().for_str(s1, s2).ndist(),
+        "damerau_levenshtein" => DamerauLevenshtein::default().for_str(s1, s2).ndist(),
+        "entropy_ncd" => EntropyNCD::default().for_str(s1, s2).ndist(),
+        "hamming" => Hamming::default().for_str(s1, s2).ndist(),
+        "jaccard" => Jaccard::default().for_str(s1, s2).ndist(),
+        "jaro" => Jaro::default().for_str(s1, s2).ndist(),
+        "jaro_winkler" => JaroWinkler::default().for_str(s1, s2).ndist(),
+        "longest_common_subsequence" => LCSSeq::default().for_str(s1, s2).ndist(),
+        "longest_common_substring" => LCSStr::default().for_str(s1, s2).ndist(),
+        "length" => Length::default().for_str(s1, s2).ndist(),
+        "lig3" => LIG3::default().for_str(s1, s2).ndist(),
+        "mlipns" => MLIPNS::default().for_str(s1, s2).ndist(),
+        "overlap" => Overlap::default().for_str(s1, s2).ndist(),
+        "prefix" => Prefix::default().for_str(s1, s2).ndist(),
+        "ratcliff_obershelp" => RatcliffObershelp::default().for_str(s1, s2).ndist(),
+        "roberts" => Roberts::default().for_str(s1, s2).ndist(),
+        "sift4_common" => Sift4Common::default().for_str(s1, s2).ndist(),
+        "sift4_simple" => Sift4Simple::default().for_str(s1, s2).ndist(),
+        "smith_waterman" => SmithWaterman::default().for_str(s1, s2).ndist(),
+        "sorensen_dice" => SorensenDice::default().for_str(s1, s2).ndist(),
+        "suffix" => Suffix::default().for_str(s1, s2).ndist(),
+        "tversky" => Tversky::default().for_str(s1, s2).ndist(),
+        "yujian_bo" => YujianBo::default().for_str(s1, s2).ndist(),
+        _ => Levenshtein::default().for_str(s1, s2).ndist(),
+    }
+}
+
 #[rustfmt::skip]
 fn list_algorithms() -> Value {
     let rows = vec![
@@ -264,3 +309,28 @@ fn test_examples() -> Result<(), nu_protocol::ShellError> {
 
     PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrSimilarity)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_matches_algorithms() {
+        let rows = list_algorithms().into_list().unwrap();
+        assert_eq!(rows.len(), ALGORITHMS.len());
+        for (row, (algo, short)) in rows.iter().zip(ALGORITHMS) {
+            let record = row.as_record().unwrap();
+            assert_eq!(record.get("algorithm"), Some(&Value::test_string(algo)));
+            assert_eq!(record.get("short"), Some(&Value::test_string(short)));
+        }
+    }
+
+    #[test]
+    fn test_algorithm_name() {
+        for (algo, short) in ALGORITHMS {
+            assert_eq!(algorithm_name(algo), Some(algo));
+            assert_eq!(algorithm_name(&short.to_uppercase()), Some(algo));
+        }
+        assert_eq!(algorithm_name("nope"), None);
+    }
+}
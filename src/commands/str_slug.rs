@@ -1,6 +1,13 @@
+use super::operate::operate;
+use deunicode::deunicode_char;
 use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
-use nu_protocol::{Category, Example, LabeledError, ShellError, Signature, Span, Type, Value};
-use slug::slugify;
+use nu_protocol::ast::{CellPath, PathMember};
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type,
+    Value, record, shell_error::generic::GenericError,
+};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 
 use crate::StrutilsPlugin;
 
@@ -15,7 +22,62 @@ impl SimplePluginCommand for StrSlug {
 
     fn signature(&self) -> Signature {
         Signature::build(self.name())
-            .input_output_types(vec![(Type::String, Type::String)])
+            .input_output_types(vec![
+                (Type::String, Type::String),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::String)),
+                ),
+                (Type::table(), Type::table()),
+                (Type::record(), Type::record()),
+            ])
+            .allow_variants_without_examples(true)
+            .rest(
+                "rest",
+                SyntaxShape::CellPath,
+                "For a data structure input, slugify the strings at the given cell paths.",
+            )
+            .named(
+                "separator",
+                SyntaxShape::String,
+                "Separator between words. (default -)",
+                Some('s'),
+            )
+            .switch("keep-case", "Don't lowercase the slug.", Some('k'))
+            .named(
+                "max-length",
+                SyntaxShape::Int,
+                "Maximum length of the slug, cut at a word boundary.",
+                Some('m'),
+            )
+            .named(
+                "stopwords",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "Words to leave out, compared without case.",
+                None,
+            )
+            .named(
+                "allow",
+                SyntaxShape::String,
+                "Characters to keep in words, such as . for file names.",
+                Some('a'),
+            )
+            .switch(
+                "unique",
+                "Make colliding slugs unique with a number like -2.",
+                Some('u'),
+            )
+            .switch(
+                "hash",
+                "Make colliding slugs unique with a short hash instead, implies --unique. Write --hash, not --unique hash.",
+                None,
+            )
+            .named(
+                "existing",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "Slugs that are already taken, implies --unique.",
+                None,
+            )
             .category(Category::Strings)
     }
 
@@ -23,16 +85,71 @@ impl SimplePluginCommand for StrSlug {
         "Convert a string to a slug (URL/filename friendly)."
     }
 
+    fn extra_description(&self) -> &str {
+        r"Characters are transliterated to ASCII like str deunicode, and every run of other characters becomes a single separator. With --max-length, whole words are kept as long as they fit, and a first word that is longer on its own is cut. When every word is a stopword, the words are kept.
+
+With --unique, the first string keeps its slug and later strings whose slug is taken get a suffix, across all strings of a list or at all cell paths of a table. The suffix is a counter starting at 2, or with --hash, six hex digits hashed from the original string. It fits within --max-length by leaving out words, and a slug that has no room left for its suffix is an error. Write --hash rather than --unique hash, which would read hash as a cell path and is rejected."
+    }
+
     fn search_terms(&self) -> Vec<&str> {
-        vec!["convert", "slug", "url", "filename"]
+        vec!["convert", "slug", "url", "filename", "unique"]
     }
 
     fn examples(&self) -> Vec<Example<'_>> {
-        vec![Example {
-            description: "Slugify a string",
-            example: r#""Hello, World!" | str slug"#,
-            result: Some(Value::test_string("hello-world")),
-        }]
+        vec![
+            Example {
+                description: "Slugify a string",
+                example: r#""Hello, World!" | str slug"#,
+                result: Some(Value::test_string("hello-world")),
+            },
+            Example {
+                description: "Use underscores and keep the case",
+                example: r#""Hello, World!" | str slug --separator _ --keep-case"#,
+                result: Some(Value::test_string("Hello_World")),
+            },
+            Example {
+                description: "Limit the length without cutting words",
+                example: r#""The quick brown fox jumps" | str slug --max-length 15"#,
+                result: Some(Value::test_string("the-quick-brown")),
+            },
+            Example {
+                description: "Leave out stopwords",
+                example: r#""The Lord of the Rings" | str slug --stopwords [a an the of]"#,
+                result: Some(Value::test_string("lord-rings")),
+            },
+            Example {
+                description: "Keep dots for a file name",
+                example: r#""Quarterly Report v2.1.pdf" | str slug --allow ".""#,
+                result: Some(Value::test_string("quarterly-report-v2.1.pdf")),
+            },
+            Example {
+                description: "Number colliding slugs in a list",
+                example: r#"["Intro" "Setup" "intro!" "Intro"] | str slug --unique"#,
+                result: Some(Value::test_list(vec![
+                    Value::test_string("intro"),
+                    Value::test_string("setup"),
+                    Value::test_string("intro-2"),
+                    Value::test_string("intro-3"),
+                ])),
+            },
+            Example {
+                description: "Avoid slugs that are already taken",
+                example: r#""Intro" | str slug --existing [intro intro-2]"#,
+                result: Some(Value::test_string("intro-3")),
+            },
+            Example {
+                description: "Add a short hash to colliding slugs in a column of a table",
+                example: r#"[[title]; ["Hello World"] ["Hello, World!"]] | str slug --hash title"#,
+                result: Some(Value::test_list(vec![
+                    Value::test_record(record! {
+                        "title" => Value::test_string("hello-world"),
+                    }),
+                    Value::test_record(record! {
+                        "title" => Value::test_string("hello-world-6fe316"),
+                    }),
+                ])),
+            },
+        ]
     }
 
     fn run(
@@ -42,13 +159,249 @@ impl SimplePluginCommand for StrSlug {
         call: &EvaluatedCall,
         input: &Value,
     ) -> Result<Value, LabeledError> {
-        Ok(do_slug(input, call.head))
+        let paths: Vec<CellPath> = call.rest(0)?;
+        let existing: Option<Vec<String>> = call.get_flag("existing")?;
+        let unique = match call.has_flag("hash")? {
+            true => Some(Unique::Hash),
+            false if call.has_flag("unique")? || existing.is_some() => Some(Unique::Number),
+            false => None,
+        };
+        // `--unique hash` parses as --unique and a cell path, which would slug a column named
+        // hash, or fail on a string
+        if unique.is_some() {
+            let hash = call.positional.iter().find(|path| match path {
+                Value::CellPath { val, .. } => {
+                    matches!(val.members.as_slice(), [PathMember::String { val, .. }] if val == "hash")
+                }
+                _ => false,
+            });
+            if let Some(path) = hash {
+                return Err(LabeledError::new("Ambiguous cell path hash")
+                    .with_label("read as a cell path, not as a kind of suffix", path.span())
+                    .with_help("Use --hash for hashed suffixes; to slug a column named hash, rename it first"));
+            }
+        }
+        let separator: String = call.get_flag("separator")?.unwrap_or_else(|| "-".into());
+        let max_length = match call.get_flag::<Spanned<i64>>("max-length")? {
+            Some(length) if length.item < 1 => {
+                return Err(LabeledError::new("Invalid maximum length")
+                    .with_label("expected a positive number", length.span));
+            }
+            Some(length) => {
+                // room for a character of the slug, the separator and the shortest suffix
+                let min = match unique {
+                    Some(Unique::Number) => separator.chars().count() + 2,
+                    Some(Unique::Hash) => separator.chars().count() + 7,
+                    None => 1,
+                };
+                if (length.item as usize) < min {
+                    return Err(
+                        LabeledError::new("Maximum length too short for unique slugs")
+                            .with_label(format!("expected at least {min}"), length.span),
+                    );
+                }
+                Some(length.item as usize)
+            }
+            None => None,
+        };
+        let slug = Slug {
+            separator,
+            keep_case: call.has_flag("keep-case")?,
+            max_length,
+            stopwords: call
+                .get_flag::<Vec<String>>("stopwords")?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|word| word.to_lowercase())
+                .collect(),
+            allow: call
+                .get_flag::<String>("allow")?
+                .map(|allow| allow.chars().collect())
+                .unwrap_or_default(),
+            unique,
+            taken: RefCell::new(Taken {
+                slugs: existing.unwrap_or_default().into_iter().collect(),
+                next: HashMap::new(),
+            }),
+        };
+        let head = call.head;
+
+        Ok(operate(input, &paths, head, &|value| {
+            do_slug(value, &slug, head)
+        }))
     }
 }
 
-fn do_slug(input: &Value, head: Span) -> Value {
+#[derive(Clone, Copy)]
+enum Unique {
+    Number,
+    Hash,
+}
+
+#[derive(Default)]
+struct Taken {
+    /// Slugs given out so far and the existing ones.
+    slugs: HashSet<String>,
+    /// The next suffix to try for each slug that collided.
+    next: HashMap<String, u64>,
+}
+
+struct Slug {
+    separator: String,
+    keep_case: bool,
+    max_length: Option<usize>,
+    /// Lowercase words to leave out.
+    stopwords: Vec<String>,
+    allow: Vec<char>,
+    unique: Option<Unique>,
+    taken: RefCell<Taken>,
+}
+
+impl Default for Slug {
+    fn default() -> Self {
+        Slug {
+            separator: "-".into(),
+            keep_case: false,
+            max_length: None,
+            stopwords: vec![],
+            allow: vec![],
+            unique: None,
+            taken: RefCell::default(),
+        }
+    }
+}
+
+impl Slug {
+    /// Slugify `text`, which matches `slug::slugify` with the default options.
+    fn slugify(&self, text: &str) -> String {
+        self.join(&self.words(text), self.max_length)
+    }
+
+    /// Slugify `text` with a suffix when the slug is already taken, and take the result. It is
+    /// an error when the suffix doesn't fit in the maximum length.
+    fn unique_slugify(&self, text: &str, unique: Unique) -> Result<String, String> {
+        let words = self.words(text);
+        let slug = self.join(&words, self.max_length);
+        let mut taken = self.taken.borrow_mut();
+        if taken.slugs.insert(slug.clone()) {
+            return Ok(slug);
+        }
+
+        let mut attempt = taken.next.get(&slug).copied().unwrap_or(2);
+        let result = loop {
+            let suffix = match unique {
+                Unique::Number => attempt.to_string(),
+                Unique::Hash => short_hash(text, attempt),
+            };
+            attempt += 1;
+            let max_length = match self.max_length {
+                Some(max) => match max.checked_sub(self.separator.chars().count() + suffix.len()) {
+                    Some(length) if length > 0 => Some(length),
+                    _ => return Err(format!("No room for the suffix {suffix} of slug {slug}")),
+                },
+                None => None,
+            };
+            let candidate = match self.join(&words, max_length) {
+                base if base.is_empty() => suffix,
+                base => base + &self.separator + &suffix,
+            };
+            if taken.slugs.insert(candidate.clone()) {
+                break candidate;
+            }
+        };
+        taken.next.insert(slug, attempt);
+
+        Ok(result)
+    }
+
+    /// The words of the slug, transliterated and without stopwords.
+    fn words(&self, text: &str) -> Vec<String> {
+        let mut words = vec![];
+        let mut word = String::new();
+        let mut push = |c: char, word: &mut String| {
+            if c.is_ascii_alphanumeric() || self.allow.contains(&c) {
+                word.push(if self.keep_case {
+                    c
+                } else {
+                    c.to_ascii_lowercase()
+                });
+            } else if !word.is_empty() {
+                words.push(std::mem::take(word));
+            }
+        };
+        for c in text.chars() {
+            if c.is_ascii() || self.allow.contains(&c) {
+                push(c, &mut word);
+            } else {
+                deunicode_char(c)
+                    .unwrap_or("-")
+                    .chars()
+                    .for_each(|c| push(c, &mut word));
+            }
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+
+        let kept: Vec<String> = words
+            .iter()
+            .filter(|word| !self.stopwords.contains(&word.to_lowercase()))
+            .cloned()
+            .collect();
+        if kept.is_empty() { words } else { kept }
+    }
+
+    /// Join words with the separator, keeping as many whole words as fit in `max_length`.
+    fn join(&self, words: &[String], max_length: Option<usize>) -> String {
+        let Some(max_length) = max_length else {
+            return words.join(&self.separator);
+        };
+        let mut slug = String::new();
+        for word in words {
+            let separator = if slug.is_empty() { "" } else { &self.separator };
+            if slug.chars().count() + separator.chars().count() + word.chars().count() > max_length
+            {
+                if slug.is_empty() {
+                    slug = word.chars().take(max_length).collect();
+                }
+                break;
+            }
+            slug.push_str(separator);
+            slug.push_str(word);
+        }
+
+        slug
+    }
+}
+
+/// Six hex digits of an FNV-1a hash of `text` and `attempt`, which is stable across releases.
+fn short_hash(text: &str, attempt: u64) -> String {
+    let hash = text
+        .bytes()
+        .chain(attempt.to_le_bytes())
+        .fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+    format!("{:06x}", hash & 0xffffff)
+}
+
+fn do_slug(input: &Value, slug: &Slug, head: Span) -> Value {
     match input {
-        Value::String { val, .. } => Value::string(slugify(val), head),
+        Value::String { val, .. } => match slug.unique {
+            Some(unique) => match slug.unique_slugify(val, unique) {
+                Ok(result) => Value::string(result, head),
+                Err(error) => Value::error(
+                    ShellError::Generic(GenericError::new(
+                        error,
+                        "the slug and its suffix are longer than --max-length",
+                        head,
+                    )),
+                    head,
+                ),
+            },
+            None => Value::string(slug.slugify(val), head),
+        },
         Value::Error { .. } => input.clone(),
         _ => Value::error(
             ShellError::OnlySupportsThisInputType {
@@ -79,7 +432,7 @@ mod tests {
         let input = Value::string("Hello World", Span::test_data());
         let expected = "hello-world";
 
-        match do_slug(&input, Span::test_data()) {
+        match do_slug(&input, &Slug::default(), Span::test_data()) {
             Value::String { val, .. } => assert_eq!(val, expected),
             _ => panic!("Expected string value"),
         }
@@ -90,7 +443,7 @@ mod tests {
         let input = Value::string("User@example.com", Span::test_data());
         let expected = "user-example-com";
 
-        match do_slug(&input, Span::test_data()) {
+        match do_slug(&input, &Slug::default(), Span::test_data()) {
             Value::String { val, .. } => assert_eq!(val, expected),
             _ => panic!("Expected string value"),
         }
@@ -101,7 +454,7 @@ mod tests {
         let input = Value::string("test--it   now!", Span::test_data());
         let expected = "test-it-now";
 
-        match do_slug(&input, Span::test_data()) {
+        match do_slug(&input, &Slug::default(), Span::test_data()) {
             Value::String { val, .. } => assert_eq!(val, expected),
             _ => panic!("Expected string value"),
         }
@@ -112,20 +465,123 @@ mod tests {
         let input = Value::string("  --test_-_cool", Span::test_data());
         let expected = "test-cool";
 
-        match do_slug(&input, Span::test_data()) {
+        match do_slug(&input, &Slug::default(), Span::test_data()) {
             Value::String { val, .. } => assert_eq!(val, expected),
             _ => panic!("Expected string value"),
         }
     }
 
+    #[test]
+    fn test_matches_slugify() {
+        let slug = Slug::default();
+        for text in [
+            "My Test String!!!1!1",
+            "test\nit   now!",
+            "You & Me",
+            "北京 and 東京",
+            "naïve café—menu",
+            "",
+            "!!!",
+        ] {
+            assert_eq!(slug.slugify(text), slug::slugify(text));
+        }
+    }
+
+    #[test]
+    fn test_max_length() {
+        let slug = |max_length| Slug {
+            max_length: Some(max_length),
+            ..Slug::default()
+        };
+        assert_eq!(slug(9).slugify("hello big world"), "hello-big");
+        assert_eq!(slug(8).slugify("hello big world"), "hello");
+        assert_eq!(slug(3).slugify("hello world"), "hel");
+        assert_eq!(slug(50).slugify("hello world"), "hello-world");
+    }
+
+    #[test]
+    fn test_unique() {
+        let slug = |unique, max_length| Slug {
+            unique: Some(unique),
+            max_length,
+            ..Slug::default()
+        };
+        let numbered = slug(Unique::Number, Some(9));
+        let slugs: Vec<_> = ["hello big world", "hello big", "hello big", "!", "?"]
+            .iter()
+            .map(|text| numbered.unique_slugify(text, Unique::Number).unwrap())
+            .collect();
+        assert_eq!(slugs, ["hello-big", "hello-2", "hello-3", "", "2"]);
+
+        let hashed = slug(Unique::Hash, None);
+        let unique = |text| hashed.unique_slugify(text, Unique::Hash).unwrap();
+        let first = unique("Same");
+        let second = unique("Same");
+        let third = unique("Same");
+        assert_eq!(first, "same");
+        assert_eq!(second, format!("same-{}", short_hash("Same", 2)));
+        assert_eq!(third, format!("same-{}", short_hash("Same", 3)));
+        assert_ne!(second, third);
+    }
+
+    #[test]
+    fn test_unique_suffix_fits_max_length() {
+        let slug = |unique, max_length| Slug {
+            unique: Some(unique),
+            max_length: Some(max_length),
+            ..Slug::default()
+        };
+        let numbered = slug(Unique::Number, 3);
+        for expected in [
+            "hel", "h-2", "h-3", "h-4", "h-5", "h-6", "h-7", "h-8", "h-9",
+        ] {
+            let result = numbered.unique_slugify("hello world", Unique::Number);
+            assert_eq!(result.unwrap(), expected);
+        }
+        // "h-10" is longer than 3
+        assert!(
+            numbered
+                .unique_slugify("hello world", Unique::Number)
+                .is_err()
+        );
+
+        let hashed = slug(Unique::Hash, 8);
+        assert_eq!(
+            hashed.unique_slugify("hello world", Unique::Hash).unwrap(),
+            "hello"
+        );
+        let second = hashed.unique_slugify("hello world", Unique::Hash).unwrap();
+        assert_eq!(second, format!("h-{}", short_hash("hello world", 2)));
+        assert!(second.len() <= 8);
+    }
+
     #[test]
     fn test_unicode_characters() {
         let input = Value::string("Æúű--cool?", Span::test_data());
         let expected = "aeuu-cool";
 
-        match do_slug(&input, Span::test_data()) {
+        match do_slug(&input, &Slug::default(), Span::test_data()) {
             Value::String { val, .. } => assert_eq!(val, expected),
             _ => panic!("Expected string value"),
         }
     }
+
+    #[test]
+    fn test_unique_hash_is_not_a_cell_path() {
+        use nu_plugin_test_support::PluginTest;
+
+        let mut plugin = PluginTest::new("strutils", StrutilsPlugin.into()).unwrap();
+        for source in [
+            "'Intro' | str slug --unique hash",
+            "[[hash]; [Intro]] | str slug --unique hash",
+        ] {
+            let error = plugin.eval(source).unwrap_err();
+            assert!(
+                error.to_string().contains("Ambiguous cell path hash"),
+                "{source}: {error}"
+            );
+        }
+        // without --unique, hash is an ordinary column
+        assert!(plugin.eval("[[hash]; [Intro]] | str slug hash").is_ok());
+    }
 }
@@ -0,0 +1,460 @@
+use super::str_similarity::{algorithm_name, normalized_distance};
+use crate::StrutilsPlugin;
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{
+    Category, Example, LabeledError, Signature, Span, Spanned, SyntaxShape, Type, Value, record,
+};
+use std::collections::{HashMap, HashSet};
+use textdistance::str::damerau_levenshtein;
+
+const DEFAULT_MAX_DISTANCE: usize = 2;
+/// Largest distance allowed with --fast, as every word adds all of its deletions to the index.
+const MAX_FAST_DISTANCE: usize = 3;
+const DEFAULT_LIMIT: usize = 5;
+
+pub struct StrSuggest;
+
+impl SimplePluginCommand for StrSuggest {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str suggest"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::String, Type::table()),
+                (Type::list(Type::String), Type::table()),
+            ])
+            .required(
+                "dictionary",
+                SyntaxShape::Any,
+                "A list of words, a record of word frequencies, or the path to a newline-separated word file.",
+            )
+            .named(
+                "max-distance",
+                SyntaxShape::Int,
+                "Maximum damerau_levenshtein distance of a suggestion (default 2)",
+                Some('d'),
+            )
+            .named(
+                "limit",
+                SyntaxShape::Int,
+                "Maximum number of suggestions for each word (default 5)",
+                Some('l'),
+            )
+            .named(
+                "algorithm",
+                SyntaxShape::String,
+                "Rank suggestions by this `str similarity` algorithm instead of by edit distance",
+                Some('a'),
+            )
+            .switch(
+                "fast",
+                "Look up candidates in a SymSpell-style index of deletions instead of comparing against every word (at most --max-distance 3)",
+                Some('f'),
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Suggest corrections for misspelled words from a dictionary."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"Suggestions are ranked by the fewest edits and then by how frequent the word is in the dictionary. Repeated words in a list count towards their frequency, and each line of a dictionary file may contain a word followed by its frequency.
+
+When an algorithm is given, suggestions within the maximum distance are ranked by that algorithm's normalized similarity instead, and a similarity column is added. See `str similarity --list` for the available algorithms."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["spelling", "typo", "did you mean", "correct", "similarity"]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Suggest corrections for a misspelled word",
+                example: "'speling' | str suggest [spelling spewing selling]",
+                result: Some(Value::test_list(vec![
+                    suggestion_record("spelling", 1, 1),
+                    suggestion_record("spewing", 1, 1),
+                    suggestion_record("selling", 2, 1),
+                ])),
+            },
+            Example {
+                description: "Prefer the most frequent words when the distance is the same",
+                example: "'teh' | str suggest {the: 500, tea: 20, ten: 45}",
+                result: Some(Value::test_list(vec![
+                    suggestion_record("the", 1, 500),
+                    suggestion_record("ten", 1, 45),
+                    suggestion_record("tea", 1, 20),
+                ])),
+            },
+            Example {
+                description: "Suggest the best correction for each word in a list",
+                example: "[speling teh] | str suggest [spelling the] --limit 1",
+                result: Some(Value::test_list(vec![
+                    Value::test_record(record! {
+                        "word" => Value::test_string("speling"),
+                        "suggestions" => Value::test_list(vec![suggestion_record("spelling", 1, 1)]),
+                    }),
+                    Value::test_record(record! {
+                        "word" => Value::test_string("teh"),
+                        "suggestions" => Value::test_list(vec![suggestion_record("the", 1, 1)]),
+                    }),
+                ])),
+            },
+            Example {
+                description: "Use a big word list file with the fast lookup",
+                example: "'recieve' | str suggest words.txt --fast",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let dictionary: Value = call.req(0)?;
+        let fast = call.has_flag("fast")?;
+        let max_distance = match call.get_flag::<Spanned<usize>>("max-distance")? {
+            Some(distance) if fast && distance.item > MAX_FAST_DISTANCE => {
+                return Err(LabeledError::new("Maximum distance too large for --fast")
+                    .with_label(
+                        format!("expected at most {MAX_FAST_DISTANCE} with --fast"),
+                        distance.span,
+                    )
+                    .with_help("Drop --fast to compare against every word instead"));
+            }
+            Some(distance) => distance.item,
+            None => DEFAULT_MAX_DISTANCE,
+        };
+        let limit = call.get_flag("limit")?.unwrap_or(DEFAULT_LIMIT);
+        let algorithm = match call.get_flag::<Spanned<String>>("algorithm")? {
+            Some(algo) => Some(algorithm_name(&algo.item).ok_or_else(|| {
+                LabeledError::new("Unknown algorithm")
+                    .with_label(
+                        format!("'{}' is not a known algorithm", algo.item),
+                        algo.span,
+                    )
+                    .with_help("Run `str similarity --list` to see the available algorithms")
+            })?),
+            None => None,
+        };
+
+        let words = load_dictionary(&dictionary, engine)?;
+        let dictionary = Dictionary::new(words, max_distance, fast);
+        let suggest = |word: &str| {
+            let rows = dictionary
+                .suggest(word, max_distance, algorithm)
+                .into_iter()
+                .take(limit)
+                .map(|s| s.into_value(call.head))
+                .collect();
+            Value::list(rows, call.head)
+        };
+
+        match input {
+            Value::String { val, .. } => Ok(suggest(val)),
+            Value::List { vals, .. } => {
+                let rows = vals
+                    .iter()
+                    .map(|val| match val {
+                        Value::String { val: word, .. } => Ok(Value::record(
+                            record! {
+                                "word" => Value::string(word, call.head),
+                                "suggestions" => suggest(word),
+                            },
+                            call.head,
+                        )),
+                        _ => Err(type_error(val)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Value::list(rows, call.head))
+            }
+            _ => Err(type_error(input)),
+        }
+    }
+}
+
+struct Suggestion<'a> {
+    word: &'a str,
+    distance: usize,
+    frequency: i64,
+    similarity: Option<f64>,
+}
+
+impl Suggestion<'_> {
+    fn into_value(self, span: Span) -> Value {
+        let mut rec = record! {
+            "suggestion" => Value::string(self.word, span),
+            "distance" => Value::int(self.distance as i64, span),
+            "frequency" => Value::int(self.frequency, span),
+        };
+        if let Some(similarity) = self.similarity {
+            rec.push("similarity", Value::float(similarity, span));
+        }
+        Value::record(rec, span)
+    }
+}
+
+struct Dictionary {
+    words: Vec<(String, i64)>,
+    // maps every deletion of up to max-distance characters to the words it came from
+    deletes: Option<HashMap<String, Vec<usize>>>,
+}
+
+impl Dictionary {
+    fn new(words: Vec<(String, i64)>, max_distance: usize, fast: bool) -> Self {
+        let deletes = fast.then(|| {
+            let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+            for (i, (word, _)) in words.iter().enumerate() {
+                for delete in deletes(word, max_distance) {
+                    index.entry(delete).or_default().push(i);
+                }
+            }
+            index
+        });
+
+        Self { words, deletes }
+    }
+
+    fn candidates(&self, word: &str, max_distance: usize) -> Vec<usize> {
+        match &self.deletes {
+            Some(index) => {
+                let found: HashSet<usize> = deletes(word, max_distance)
+                    .iter()
+                    .filter_map(|delete| index.get(delete))
+                    .flatten()
+                    .copied()
+                    .collect();
+                found.into_iter().collect()
+            }
+            None => (0..self.words.len()).collect(),
+        }
+    }
+
+    fn suggest(
+        &self,
+        word: &str,
+        max_distance: usize,
+        algorithm: Option<&str>,
+    ) -> Vec<Suggestion<'_>> {
+        let word_len = word.chars().count();
+        let mut suggestions: Vec<Suggestion> = self
+            .candidates(word, max_distance)
+            .into_iter()
+            .filter_map(|i| {
+                let (candidate, frequency) = &self.words[i];
+                if candidate.chars().count().abs_diff(word_len) > max_distance {
+                    return None;
+                }
+                let distance = damerau_levenshtein(word, candidate);
+                (distance <= max_distance).then(|| Suggestion {
+                    word: candidate,
+                    distance,
+                    frequency: *frequency,
+                    similarity: algorithm.map(|a| 1.0 - normalized_distance(a, word, candidate)),
+                })
+            })
+            .collect();
+
+        suggestions.sort_by(|a, b| {
+            let rank = match (a.similarity, b.similarity) {
+                (Some(sa), Some(sb)) => sb.total_cmp(&sa),
+                _ => a.distance.cmp(&b.distance),
+            };
+            rank.then(b.frequency.cmp(&a.frequency))
+                .then(a.word.cmp(b.word))
+        });
+        suggestions
+    }
+}
+
+/// All strings that can be made by deleting up to `max_distance` characters, including the word.
+fn deletes(word: &str, max_distance: usize) -> HashSet<String> {
+    let mut result = HashSet::from([word.to_string()]);
+    let mut frontier = vec![word.to_string()];
+    for _ in 0..max_distance {
+        let mut next = vec![];
+        for w in &frontier {
+            let chars: Vec<char> = w.chars().collect();
+            for i in 0..chars.len() {
+                let delete: String = chars[..i].iter().chain(&chars[i + 1..]).collect();
+                if result.insert(delete.clone()) {
+                    next.push(delete);
+                }
+            }
+        }
+        frontier = next;
+    }
+    result
+}
+
+fn load_dictionary(
+    dictionary: &Value,
+    engine: &EngineInterface,
+) -> Result<Vec<(String, i64)>, LabeledError> {
+    let mut frequencies: HashMap<String, i64> = HashMap::new();
+    match dictionary {
+        Value::List { vals, .. } => {
+            for val in vals {
+                let word = val.as_str().map_err(|_| {
+                    LabeledError::new("Invalid dictionary").with_label(
+                        format!("expected string, found {}", val.get_type()),
+                        val.span(),
+                    )
+                })?;
+                *frequencies.entry(word.to_string()).or_default() += 1;
+            }
+        }
+        Value::Record { val, .. } => {
+            for (word, freq) in val.iter() {
+                let freq = freq.as_int().map_err(|_| {
+                    LabeledError::new("Invalid dictionary").with_label(
+                        format!("expected int frequency, found {}", freq.get_type()),
+                        freq.span(),
+                    )
+                })?;
+                *frequencies.entry(word.to_string()).or_default() += freq;
+            }
+        }
+        Value::String { val, .. } => {
+            let cwd = engine.get_current_dir()?;
+            let path = nu_path::expand_path_with(val, cwd, true);
+            let contents = std::fs::read_to_string(&path).map_err(|err| {
+                LabeledError::new("Could not read dictionary file")
+                    .with_label(format!("{}: {err}", path.display()), dictionary.span())
+            })?;
+            for line in contents.lines() {
+                let mut parts = line.split_whitespace();
+                let Some(word) = parts.next() else {
+                    continue;
+                };
+                let freq = parts.next().and_then(|f| f.parse().ok()).unwrap_or(1);
+                *frequencies.entry(word.to_string()).or_default() += freq;
+            }
+        }
+        _ => {
+            return Err(LabeledError::new("Invalid dictionary")
+                .with_label(
+                    format!("expected list, record or path, found {}", dictionary.get_type()),
+                    dictionary.span(),
+                )
+                .with_help("Pass a list of words, a record of word frequencies, or the path to a word file"));
+        }
+    }
+
+    Ok(frequencies.into_iter().collect())
+}
+
+fn type_error(input: &Value) -> LabeledError {
+    LabeledError::new("Type mismatch")
+        .with_label(
+            format!(
+                "expected string or list of strings, found {}",
+                input.get_type()
+            ),
+            input.span(),
+        )
+        .with_help("Only strings and lists of strings are supported.")
+}
+
+fn suggestion_record(word: &str, distance: i64, frequency: i64) -> Value {
+    Value::test_record(record! {
+        "suggestion" => Value::test_string(word),
+        "distance" => Value::test_int(distance),
+        "frequency" => Value::test_int(frequency),
+    })
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrSuggest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary(words: &[(&str, i64)], fast: bool) -> Dictionary {
+        let words = words.iter().map(|(w, f)| (w.to_string(), *f)).collect();
+        Dictionary::new(words, DEFAULT_MAX_DISTANCE, fast)
+    }
+
+    fn suggested(dictionary: &Dictionary, word: &str, algorithm: Option<&str>) -> Vec<String> {
+        dictionary
+            .suggest(word, DEFAULT_MAX_DISTANCE, algorithm)
+            .into_iter()
+            .map(|s| s.word.to_string())
+            .collect()
+    }
+
+    const WORDS: [(&str, i64); 6] = [
+        ("receive", 30),
+        ("recipe", 10),
+        ("relieve", 5),
+        ("believe", 20),
+        ("deceive", 2),
+        ("xylophone", 1),
+    ];
+
+    #[test]
+    fn test_fast_path_matches_full_scan() {
+        let slow = dictionary(&WORDS, false);
+        let fast = dictionary(&WORDS, true);
+
+        for word in ["recieve", "beleive", "recipie", "xylofone", "zzz"] {
+            assert_eq!(suggested(&slow, word, None), suggested(&fast, word, None));
+        }
+    }
+
+    #[test]
+    fn test_rank_by_distance_then_frequency() {
+        let dict = dictionary(&WORDS, false);
+        assert_eq!(
+            suggested(&dict, "recieve", None),
+            vec!["receive", "relieve", "believe", "recipe", "deceive"]
+        );
+    }
+
+    #[test]
+    fn test_rank_by_algorithm() {
+        let dict = dictionary(&WORDS, false);
+        let suggestions = dict.suggest("recieve", DEFAULT_MAX_DISTANCE, Some("jaro_winkler"));
+        assert!(suggestions.iter().all(|s| s.similarity.is_some()));
+        assert_eq!(suggestions[0].word, "receive");
+    }
+
+    #[test]
+    fn test_deletes() {
+        let d = deletes("abc", 1);
+        assert_eq!(d.len(), 4);
+        assert!(d.contains("abc") && d.contains("bc") && d.contains("ac") && d.contains("ab"));
+    }
+
+    #[test]
+    fn test_fast_max_distance() {
+        use nu_plugin_test_support::PluginTest;
+
+        let mut plugin = PluginTest::new("strutils", StrutilsPlugin.into()).unwrap();
+        assert!(
+            plugin
+                .eval("'recieve' | str suggest [receive] --fast --max-distance 4")
+                .is_err()
+        );
+        assert!(
+            plugin
+                .eval("'recieve' | str suggest [receive] --max-distance 4")
+                .is_ok()
+        );
+    }
+}
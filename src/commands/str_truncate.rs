@@ -0,0 +1,245 @@
+use super::operate::operate;
+use super::str_wrap::{resolve_width, terminal_width};
+use super::width::{map_lines, segments, text_width};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::ast::CellPath;
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, Spanned, SyntaxShape, Type,
+    Value, record,
+};
+
+use crate::StrutilsPlugin;
+
+pub struct StrTruncate;
+
+impl SimplePluginCommand for StrTruncate {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str truncate"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::String, Type::String),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::String)),
+                ),
+                (Type::table(), Type::table()),
+                (Type::record(), Type::record()),
+            ])
+            .allow_variants_without_examples(true)
+            .rest(
+                "rest",
+                SyntaxShape::CellPath,
+                "For a data structure input, truncate the strings at the given cell paths.",
+            )
+            .required_named(
+                "width",
+                SyntaxShape::OneOf(vec![SyntaxShape::Int, SyntaxShape::String]),
+                "Maximum width in columns, an offset from the terminal width like -4, or a percentage of it like 50%.",
+                Some('w'),
+            )
+            .named(
+                "ellipsis",
+                SyntaxShape::String,
+                "Marks where text was cut. (default …)",
+                Some('e'),
+            )
+            .named(
+                "side",
+                SyntaxShape::String,
+                "Where to cut the text: end, start or middle. (default end)",
+                Some('s'),
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Shorten strings to a display width without splitting grapheme clusters."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"The width is measured like str width, and the ellipsis counts towards it. Strings that already fit are left as they are, and each line of text with several lines is truncated on its own. ANSI escape sequences in the part that is cut are kept after the ellipsis, so styles are still reset."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["shorten", "ellipsis", "cut", "elide", "width", "columns"]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Truncate a string to 8 columns",
+                example: r#""Hello world!" | str truncate --width 8"#,
+                result: Some(Value::test_string("Hello w…")),
+            },
+            Example {
+                description: "Count wide characters as two columns",
+                example: r#""日本語のテキスト" | str truncate --width 7"#,
+                result: Some(Value::test_string("日本語…")),
+            },
+            Example {
+                description: "Cut the middle of a path",
+                example: r#""/home/user/projects/nu_plugin_strutils" | str truncate --width 20 --side middle --ellipsis "...""#,
+                result: Some(Value::test_string("/home/use...strutils")),
+            },
+            Example {
+                description: "Keep the end of a file name",
+                example: r#""report-2024-final.txt" | str truncate --width 10 --side start"#,
+                result: Some(Value::test_string("…final.txt")),
+            },
+            Example {
+                description: "Truncate a column of a table",
+                example: r#"[[title]; ["A rather long title"]] | str truncate --width 10 title"#,
+                result: Some(Value::test_list(vec![Value::test_record(record! {
+                    "title" => Value::test_string("A rather …"),
+                })])),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let paths: Vec<CellPath> = call.rest(0)?;
+        let width: Value = call.get_flag("width")?.ok_or_else(|| {
+            LabeledError::new("Missing --width").with_label("missing flag", call.head)
+        })?;
+        let width = resolve_width(&width, || terminal_width(engine, call.head))?;
+        let ellipsis = call
+            .get_flag("ellipsis")?
+            .unwrap_or_else(|| "…".to_string());
+        let side = match call.get_flag("side")? {
+            Some(side) => Side::from_name(side)?,
+            None => Side::End,
+        };
+        let head = call.head;
+
+        Ok(operate(input, &paths, head, &|value| {
+            do_truncate(value, width, &ellipsis, side, head)
+        }))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    End,
+    Start,
+    Middle,
+}
+
+impl Side {
+    fn from_name(name: Spanned<String>) -> Result<Self, LabeledError> {
+        match name.item.as_str() {
+            "end" => Ok(Side::End),
+            "start" => Ok(Side::Start),
+            "middle" => Ok(Side::Middle),
+            _ => Err(LabeledError::new("Invalid side")
+                .with_label("expected end, start or middle", name.span)),
+        }
+    }
+}
+
+fn do_truncate(input: &Value, width: usize, ellipsis: &str, side: Side, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => Value::string(
+            map_lines(val, |line| truncate(line, width, ellipsis, side)),
+            head,
+        ),
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+fn truncate(line: &str, width: usize, ellipsis: &str, side: Side) -> String {
+    let segments = segments(line);
+    if segments.iter().map(|(_, width)| width).sum::<usize>() <= width {
+        return line.to_string();
+    }
+    // an ellipsis that doesn't fit is left out
+    let (ellipsis, budget) = match width.checked_sub(text_width(ellipsis)) {
+        Some(budget) => (ellipsis, budget),
+        None => ("", width),
+    };
+    let (left, right) = match side {
+        Side::End => (budget, 0),
+        Side::Start => (0, budget),
+        Side::Middle => (budget - budget / 2, budget / 2),
+    };
+
+    let mut end = 0;
+    let mut used = 0;
+    while end < segments.len() && used + segments[end].1 <= left {
+        used += segments[end].1;
+        end += 1;
+    }
+    let mut start = segments.len();
+    used = 0;
+    while start > end && used + segments[start - 1].1 <= right {
+        used += segments[start - 1].1;
+        start -= 1;
+    }
+
+    let mut result: String = segments[..end].iter().map(|(text, _)| *text).collect();
+    result.push_str(ellipsis);
+    result.extend(
+        segments[end..start]
+            .iter()
+            .filter(|(text, _)| text.starts_with('\x1b'))
+            .map(|(text, _)| *text),
+    );
+    result.extend(segments[start..].iter().map(|(text, _)| *text));
+
+    result
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrTruncate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_keeps_graphemes_whole() {
+        let family = "👨‍👩‍👧";
+        assert_eq!(
+            truncate(&family.repeat(3), 5, "…", Side::End),
+            family.repeat(2) + "…"
+        );
+        // a wide character that doesn't fit leaves a column unused
+        assert_eq!(truncate("日本語", 4, "…", Side::End), "日…");
+        assert_eq!(
+            truncate("e\u{301}e\u{301}e\u{301}", 2, "…", Side::End),
+            "e\u{301}…"
+        );
+    }
+
+    #[test]
+    fn test_truncate_keeps_escapes() {
+        assert_eq!(
+            truncate("\x1b[31mred text\x1b[0m", 5, "…", Side::End),
+            "\x1b[31mred …\x1b[0m"
+        );
+        assert_eq!(truncate("abcdef", 2, "...", Side::End), "ab");
+    }
+}
@@ -0,0 +1,179 @@
+use super::str_expand_tabs::TabStops;
+use super::width::{escape_len, next_grapheme};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, SyntaxShape, Type, Value,
+};
+
+use crate::StrutilsPlugin;
+
+pub struct StrUnexpandTabs;
+
+impl SimplePluginCommand for StrUnexpandTabs {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str unexpand-tabs"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![(Type::String, Type::String)])
+            .named(
+                "tabs",
+                SyntaxShape::OneOf(vec![
+                    SyntaxShape::Int,
+                    SyntaxShape::List(Box::new(SyntaxShape::Int)),
+                ]),
+                "Tab stops every n columns, or a list of tab stop columns. (default 8)",
+                Some('t'),
+            )
+            .switch(
+                "leading-only",
+                "Only convert the indentation at the start of each line.",
+                Some('l'),
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Replace runs of spaces that reach a tab stop with tabs."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"Like the unexpand command from coreutils with --all. A single space before a tab stop is left as a space. Columns are counted in display width, so wide characters such as CJK take two columns and ANSI escape sequences take none."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["unexpand", "tab", "spaces", "column"]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Convert spaces to tabs with tab stops every 4 columns",
+                example: r#""a   bc  d\n        indented" | str unexpand-tabs --tabs 4"#,
+                result: Some(Value::test_string("a\tbc\td\n\t\tindented")),
+            },
+            Example {
+                description: "Only convert the indentation",
+                example: r#""        key     value" | str unexpand-tabs --leading-only"#,
+                result: Some(Value::test_string("\tkey     value")),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let stops = TabStops::from_flag(call.get_flag("tabs")?)?;
+        let leading_only = call.has_flag("leading-only")?;
+
+        Ok(do_unexpand(input, &stops, leading_only, call.head))
+    }
+}
+
+fn do_unexpand(input: &Value, stops: &TabStops, leading_only: bool, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => Value::string(unexpand(val, stops, leading_only), head),
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+fn unexpand(text: &str, stops: &TabStops, leading_only: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let mut column = 0;
+        // the column where the current run of spaces and tabs started
+        let mut blanks: Option<usize> = None;
+        let mut leading = true;
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            let len = match c {
+                ' ' | '\t' if leading || !leading_only => {
+                    blanks.get_or_insert(column);
+                    column = match c {
+                        '\t' => stops.next(column).unwrap_or(column + 1),
+                        _ => column + 1,
+                    };
+                    1
+                }
+                _ => {
+                    if let Some(start) = blanks.take() {
+                        push_blanks(&mut result, start, column, stops);
+                    }
+                    if c == '\x1b' {
+                        let len = escape_len(rest);
+                        result.push_str(&rest[..len]);
+                        len
+                    } else {
+                        leading &= c == ' ' || c == '\t';
+                        let (grapheme, width) = match c {
+                            '\t' => ("\t", stops.next(column).unwrap_or(column + 1) - column),
+                            _ => next_grapheme(rest),
+                        };
+                        column += width;
+                        result.push_str(grapheme);
+                        grapheme.len()
+                    }
+                }
+            };
+            rest = &rest[len..];
+        }
+        if let Some(start) = blanks {
+            push_blanks(&mut result, start, column, stops);
+        }
+    }
+
+    result
+}
+
+/// Fill the columns from `start` to `end` with tabs where they reach a tab stop, and spaces.
+fn push_blanks(result: &mut String, start: usize, end: usize, stops: &TabStops) {
+    let mut column = start;
+    while let Some(stop) = stops.next(column).filter(|&stop| stop <= end) {
+        if stop - column == 1 && stop == end {
+            break;
+        }
+        result.push('\t');
+        column = stop;
+    }
+    result.extend(std::iter::repeat_n(' ', end - column));
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrUnexpandTabs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unexpand() {
+        let every = TabStops::Every(4);
+        assert_eq!(unexpand("a b", &every, false), "a b");
+        assert_eq!(unexpand("abc d", &every, false), "abc d");
+        assert_eq!(unexpand("ab  \t  x", &every, false), "ab\t\t  x");
+        assert_eq!(unexpand("日本 x  ", &every, false), "日本 x\t");
+        // no tabs after the last listed stop
+        let at = TabStops::At(vec![4]);
+        assert_eq!(unexpand("        x", &at, false), "\t    x");
+    }
+}
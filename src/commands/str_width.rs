@@ -0,0 +1,114 @@
+use super::operate::operate;
+use super::width::text_width;
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::ast::CellPath;
+use nu_protocol::{
+    Category, Example, LabeledError, ShellError, Signature, Span, SyntaxShape, Type, Value, record,
+};
+
+use crate::StrutilsPlugin;
+
+pub struct StrWidth;
+
+impl SimplePluginCommand for StrWidth {
+    type Plugin = StrutilsPlugin;
+
+    fn name(&self) -> &str {
+        "str width"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::String, Type::Int),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::Int)),
+                ),
+                (Type::table(), Type::table()),
+                (Type::record(), Type::record()),
+            ])
+            .allow_variants_without_examples(true)
+            .rest(
+                "rest",
+                SyntaxShape::CellPath,
+                "For a data structure input, measure the strings at the given cell paths.",
+            )
+            .category(Category::Strings)
+    }
+
+    fn description(&self) -> &str {
+        "Measure the display width of strings in terminal columns."
+    }
+
+    fn extra_description(&self) -> &str {
+        r"Unlike str length, the width is counted per grapheme cluster: wide characters such as CJK and emoji take two columns, combining marks and zero width characters take none, and an emoji sequence joined with zero width joiners counts as one emoji. ANSI escape sequences and control characters take no columns. For text with several lines, the width is that of the widest line."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["length", "columns", "display", "terminal", "emoji", "cjk"]
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Measure wide characters and emoji",
+                example: r#"["abc" "日本語" "👨‍👩‍👧" "e\u{301}"] | str width"#,
+                result: Some(Value::test_list(vec![
+                    Value::test_int(3),
+                    Value::test_int(6),
+                    Value::test_int(2),
+                    Value::test_int(1),
+                ])),
+            },
+            Example {
+                description: "Ignore ANSI escape sequences",
+                example: r#"$"(ansi red)red(ansi reset)" | str width"#,
+                result: None,
+            },
+            Example {
+                description: "Measure a column of a table",
+                example: r#"[[name]; ["東京"]] | str width name"#,
+                result: Some(Value::test_list(vec![Value::test_record(record! {
+                    "name" => Value::test_int(4),
+                })])),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &StrutilsPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let paths: Vec<CellPath> = call.rest(0)?;
+        let head = call.head;
+
+        Ok(operate(input, &paths, head, &|value| do_width(value, head)))
+    }
+}
+
+fn do_width(input: &Value, head: Span) -> Value {
+    match input {
+        Value::String { val, .. } => Value::int(text_width(val) as i64, head),
+        Value::Error { .. } => input.clone(),
+        _ => Value::error(
+            ShellError::OnlySupportsThisInputType {
+                exp_input_type: "string".into(),
+                wrong_type: input.get_type().to_string(),
+                dst_span: head,
+                src_span: input.span(),
+            },
+            head,
+        ),
+    }
+}
+
+#[test]
+fn test_examples() -> Result<(), nu_protocol::ShellError> {
+    use nu_plugin_test_support::PluginTest;
+
+    PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrWidth)
+}
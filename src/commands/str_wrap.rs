@@ -1,8 +1,17 @@
+use super::operate::operate;
+use super::str_hyphenate::load_dictionary;
+use super::width::{display_width, escape_len};
 use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::ast::{CellPath, PathMember};
+use nu_protocol::casing::Casing;
 use nu_protocol::{
-    Category, Example, LabeledError, ShellError, Signature, Span, SyntaxShape, Type, Value,
+    Category, Example, LabeledError, PipelineData, ShellError, Signature, Span, Spanned,
+    SyntaxShape, Type, Value, record,
 };
-use textwrap::{Options, WrapAlgorithm, fill};
+use std::cell::OnceCell;
+use std::slice;
+use textwrap::core::Word;
+use textwrap::{LineEnding, Options, WordSeparator, WordSplitter, WrapAlgorithm, wrap};
 
 use crate::StrutilsPlugin;
 
@@ -17,18 +26,104 @@ impl SimplePluginCommand for StrWrap {
 
     fn signature(&self) -> Signature {
         Signature::build(self.name())
-            .input_output_types(vec![(Type::String, Type::String)])
+            .input_output_types(vec![
+                (Type::String, Type::String),
+                (
+                    Type::List(Box::new(Type::String)),
+                    Type::List(Box::new(Type::String)),
+                ),
+                (Type::table(), Type::table()),
+                (Type::record(), Type::record()),
+            ])
+            .allow_variants_without_examples(true)
+            .rest(
+                "rest",
+                SyntaxShape::CellPath,
+                "For a data structure input, wrap the strings at the given cell paths.",
+            )
             .switch(
                 "optimal-fit",
                 "Wrap words using an advanced algorithm with look-ahead.",
                 Some('o'),
             )
+            .switch(
+                "reflow",
+                "Join the lines of each paragraph before wrapping, keeping lists and indented blocks.",
+                Some('r'),
+            )
+            .switch(
+                "markdown",
+                "Wrap the paragraphs and list items of a Markdown document, keeping code blocks, tables, headings and HTML as they are.",
+                Some('m'),
+            )
+            .named(
+                "prefix",
+                SyntaxShape::String,
+                "Strip this line prefix before wrapping and add it back to every line. Use auto to detect comment and quote prefixes such as ///, #, -- and > >.",
+                Some('p'),
+            )
             .named(
                 "width",
-                SyntaxShape::Int,
-                "The width in columns at which the text will be wrapped. (default 80)",
+                SyntaxShape::OneOf(vec![
+                    SyntaxShape::Int,
+                    SyntaxShape::String,
+                    SyntaxShape::Record(vec![]),
+                ]),
+                "The width in columns at which the text will be wrapped, an offset from the terminal width like -4, or a percentage of it like 50%. A record gives each column of a table its own width. (default terminal width, or 80 without a terminal)",
                 Some('w'),
             )
+            .named(
+                "initial-indent",
+                SyntaxShape::String,
+                "Indentation used for the first line of output.",
+                Some('i'),
+            )
+            .named(
+                "subsequent-indent",
+                SyntaxShape::String,
+                "Indentation used for subsequent lines of output.",
+                Some('s'),
+            )
+            .switch(
+                "break-words",
+                "Break words longer than the width into pieces. (default)",
+                None,
+            )
+            .switch(
+                "no-break-words",
+                "Allow words longer than the width to overflow the line.",
+                None,
+            )
+            .named(
+                "word-separator",
+                SyntaxShape::String,
+                "How words are found in a line: ascii or unicode-linebreak. (default unicode-linebreak)",
+                None,
+            )
+            .named(
+                "word-splitter",
+                SyntaxShape::String,
+                "How words are split into smaller pieces: none, hyphen or hyphenation. (default hyphen)",
+                None,
+            )
+            .named(
+                "hyphenate",
+                SyntaxShape::String,
+                "Break long words at syllable boundaries using the hyphenation patterns for a language: en-us, en-gb, de-1996, fr, es, it, nl, pt, ru or sv.",
+                None,
+            )
+            .named(
+                "align",
+                SyntaxShape::String,
+                "Alignment of the wrapped lines: left, right, center or justify. (default left)",
+                Some('a'),
+            )
+            .named(
+                "line-ending",
+                SyntaxShape::String,
+                "Line ending of the output: lf or crlf. Input lines can end in either. (default lf)",
+                None,
+            )
             .category(Category::Strings)
     }
 
@@ -37,7 +132,16 @@ impl SimplePluginCommand for StrWrap {
     }
 
     fn search_terms(&self) -> Vec<&str> {
-        vec!["convert", "ascii"]
+        vec![
+            "convert",
+            "ascii",
+            "refill",
+            "reflow",
+            "paragraph",
+            "comment",
+            "quote",
+            "markdown",
+        ]
     }
 
     fn examples(&self) -> Vec<Example<'_>> {
@@ -49,6 +153,16 @@ impl SimplePluginCommand for StrWrap {
                     "now is the\ntime for\nall good\nmen to\ncome to\nthe aid of\ntheir\ncountry",
                 )),
             },
+            Example {
+                description: "Wrap text four columns short of the terminal width",
+                example: r#""now is the time for all good men to come to the aid of their country" | str wrap --width -4"#,
+                result: None,
+            },
+            Example {
+                description: "Wrap text at half of the terminal width",
+                example: r#""now is the time for all good men to come to the aid of their country" | str wrap --width 50%"#,
+                result: None,
+            },
             Example {
                 description: "Wrap text at 10 columns using optimal-fit",
                 example: r#""now is the time for all good men to come to the aid of their country" | str wrap --width 10 --optimal-fit"#,
@@ -56,35 +170,348 @@ impl SimplePluginCommand for StrWrap {
                     "now is\nthe time\nfor all\ngood men\nto come\nto the aid\nof their\ncountry",
                 )),
             },
+            Example {
+                description: "Wrap text at 20 columns with a hanging indent",
+                example: r#""- now is the time for all good men to come to the aid of their country" | str wrap --width 20 --subsequent-indent "  ""#,
+                result: Some(Value::test_string(
+                    "- now is the time\n  for all good men\n  to come to the aid\n  of their country",
+                )),
+            },
+            Example {
+                description: "Rewrap text that was already wrapped, keeping the list item's hanging indent",
+                example: r#""now is the time\nfor all good men\n\n- to come to the\n  aid of their country" | str wrap --reflow --width 30"#,
+                result: Some(Value::test_string(
+                    "now is the time for all good\nmen\n\n- to come to the aid of their\n  country",
+                )),
+            },
+            Example {
+                description: "Rewrap a doc comment, keeping the comment prefix on every line",
+                example: r#""/// now is the time for all good men\n/// to come to the aid of their country" | str wrap --prefix auto --reflow --width 30"#,
+                result: Some(Value::test_string(
+                    "/// now is the time for all\n/// good men to come to the\n/// aid of their country",
+                )),
+            },
+            Example {
+                description: "Rewrap a Markdown document, keeping headings and code blocks and never splitting inline code",
+                example: r##""# Title\n\nnow is the time for all good men to come to the aid of `their country`\n\n```\nlet x = 1\n```" | str wrap --markdown --width 30"##,
+                result: Some(Value::test_string(
+                    "# Title\n\nnow is the time for all good\nmen to come to the aid of\n`their country`\n\n```\nlet x = 1\n```",
+                )),
+            },
+            Example {
+                description: "Wrap the description column of a table",
+                example: r#"[[name description]; [nu "a new type of shell"]] | str wrap description --width 10"#,
+                result: Some(Value::test_list(vec![Value::test_record(record! {
+                    "name" => Value::test_string("nu"),
+                    "description" => Value::test_string("a new type\nof shell"),
+                })])),
+            },
+            Example {
+                description: "Give each column of a table its own width",
+                example: r#"[[name description]; ["nushell plugin" "a new type of shell"]] | str wrap --width {name: 8, description: 12}"#,
+                result: Some(Value::test_list(vec![Value::test_record(record! {
+                    "name" => Value::test_string("nushell\nplugin"),
+                    "description" => Value::test_string("a new type\nof shell"),
+                })])),
+            },
+            Example {
+                description: "Wrap and justify text at 20 columns",
+                example: r#""now is the time for all good men to come to the aid of their country" | str wrap --width 20 --align justify"#,
+                result: Some(Value::test_string(
+                    "now  is the time for\nall good men to come\nto  the aid of their\ncountry",
+                )),
+            },
+            Example {
+                description: "Center text at 20 columns",
+                example: r#""now is the time for all good men" | str wrap --width 20 --align center"#,
+                result: Some(Value::test_string("now is the time for\n    all good men")),
+            },
+            Example {
+                description: "Wrap colored text so that every line keeps its color",
+                example: r#""\e[31mnow is the time for all good men\e[0m" | str wrap --width 20"#,
+                result: Some(Value::test_string(
+                    "\u{1b}[31mnow is the time for\u{1b}[0m\n\u{1b}[31mall good men\u{1b}[0m",
+                )),
+            },
+            Example {
+                description: "Wrap text only at spaces, without breaking long words, using Windows line endings",
+                example: r#""see https://www.nushell.sh/book/" | str wrap --width 10 --no-break-words --word-separator ascii --line-ending crlf"#,
+                result: Some(Value::test_string("see\r\nhttps://www.nushell.sh/book/")),
+            },
+            Example {
+                description: "Wrap German text splitting long words at syllable boundaries",
+                example: r#""Donaudampfschifffahrtsgesellschaft" | str wrap --width 12 --hyphenate de-1996"#,
+                result: Some(Value::test_string(
+                    "Donaudampf-\nschiff-\nfahrtsge-\nsellschaft",
+                )),
+            },
+            Example {
+                description: "Wrap text splitting words at hyphenation points",
+                example: r#""the internationalization of software" | str wrap --width 12 --word-splitter hyphenation"#,
+                result: Some(Value::test_string(
+                    "the interna-\ntionaliza-\ntion of\nsoftware",
+                )),
+            },
         ]
     }
 
     fn run(
         &self,
         _plugin: &StrutilsPlugin,
-        _engine: &EngineInterface,
+        engine: &EngineInterface,
         call: &EvaluatedCall,
         input: &Value,
     ) -> Result<Value, LabeledError> {
         let optimal = call.has_flag("optimal-fit")?;
-        let width = call.get_flag("width")?.unwrap_or(80usize);
+        let terminal = OnceCell::new();
+        let terminal = || *terminal.get_or_init(|| terminal_width(engine, call.head));
+        let mut columns: Vec<(CellPath, Option<usize>)> = call
+            .rest::<CellPath>(0)?
+            .into_iter()
+            .map(|path| (path, None))
+            .collect();
+        let width = match call.get_flag::<Value>("width")? {
+            Some(Value::Record { val, .. }) => {
+                // the record's columns are wrapped too, each at its own width
+                for (column, width) in val.iter() {
+                    let width = resolve_width(width, terminal)?;
+                    columns.retain(|(path, _)| path.to_column_name() != *column);
+                    let member =
+                        PathMember::string(column.clone(), false, Casing::Sensitive, call.head);
+                    columns.push((
+                        CellPath {
+                            members: vec![member],
+                        },
+                        Some(width),
+                    ));
+                }
+                terminal()
+            }
+            Some(width) => resolve_width(&width, terminal)?,
+            None => terminal(),
+        };
+        let initial_indent: Option<String> = call.get_flag("initial-indent")?;
+        let subsequent_indent: Option<String> = call.get_flag("subsequent-indent")?;
+        let break_words = call.has_flag("break-words")?;
+        let no_break_words = call.has_flag("no-break-words")?;
+        if break_words && no_break_words {
+            return Err(LabeledError::new(
+                "Only one of --break-words and --no-break-words can be used",
+            )
+            .with_label("conflicting flags", call.head));
+        }
+
+        let mut options = Options::new(width)
+            .wrap_algorithm(if optimal {
+                WrapAlgorithm::new_optimal_fit()
+            } else {
+                WrapAlgorithm::FirstFit
+            })
+            .initial_indent(initial_indent.as_deref().unwrap_or(""))
+            .subsequent_indent(subsequent_indent.as_deref().unwrap_or(""))
+            .break_words(!no_break_words);
+        if let Some(separator) = call.get_flag("word-separator")? {
+            options = options.word_separator(word_separator(separator)?);
+        }
+        let splitter: Option<Spanned<String>> = call.get_flag("word-splitter")?;
+        let hyphenate: Option<Spanned<String>> = call.get_flag("hyphenate")?;
+        match (splitter, hyphenate) {
+            (Some(_), Some(lang)) => {
+                return Err(LabeledError::new(
+                    "Only one of --word-splitter and --hyphenate can be used",
+                )
+                .with_label("conflicting flags", lang.span));
+            }
+            (Some(splitter), None) => options = options.word_splitter(word_splitter(splitter)?),
+            (None, Some(lang)) => {
+                let dictionary = load_dictionary(&lang.item, lang.span)?;
+                options = options.word_splitter(WordSplitter::Hyphenation(dictionary));
+            }
+            (None, None) => {}
+        }
+        if let Some(line_ending) = call.get_flag("line-ending")? {
+            options = options.line_ending(line_ending_from(line_ending)?);
+        }
+        let align = match call.get_flag("align")? {
+            Some(align) => Alignment::from_name(align)?,
+            None => Alignment::Left,
+        };
+        let layout = Layout {
+            align,
+            reflow: call.has_flag("reflow")?,
+            markdown: call.has_flag("markdown")?,
+            prefix: call
+                .get_flag::<String>("prefix")?
+                .map(|prefix| match prefix.as_str() {
+                    "auto" => Prefix::Auto,
+                    _ => Prefix::Literal(prefix),
+                }),
+        };
+
+        Ok(do_wrap(input, &options, &layout, &columns, call.head))
+    }
+}
+
+const DEFAULT_WIDTH: usize = 80;
+
+/// Columns of the terminal nushell runs in. The plugin's own stdio is connected to nushell rather
+/// than the terminal, so the engine is asked with `term size`, then `$env.COLUMNS`, falling back
+/// to 80 columns when there is no terminal.
+pub(crate) fn terminal_width(engine: &EngineInterface, head: Span) -> usize {
+    let term_size = || {
+        let decl = engine.find_decl("term size").ok()??;
+        let size = engine
+            .call_decl(
+                decl,
+                EvaluatedCall::new(head),
+                PipelineData::empty(),
+                true,
+                false,
+            )
+            .ok()?
+            .into_value(head)
+            .ok()?;
+        size.get_data_by_key("columns")?.as_int().ok()
+    };
+    let columns = || {
+        let columns = engine.get_env_var("COLUMNS").ok()??;
+        columns.coerce_str().ok()?.trim().parse::<i64>().ok()
+    };
+
+    term_size()
+        .filter(|&width| width > 0)
+        .or_else(|| columns().filter(|&width| width > 0))
+        .map_or(DEFAULT_WIDTH, |width| width as usize)
+}
+
+/// Resolve a `--width` of a number of columns, a negative offset from the terminal width (`-4`),
+/// or a percentage of it (`50%`). The terminal is only asked when the width is relative.
+pub(crate) fn resolve_width(
+    width: &Value,
+    terminal: impl FnOnce() -> usize,
+) -> Result<usize, LabeledError> {
+    let invalid = || {
+        LabeledError::new("Invalid width").with_label(
+            "expected a number of columns, an offset like -4 or a percentage like 50%",
+            width.span(),
+        )
+    };
+    let columns = match width {
+        Value::Int { val, .. } => *val,
+        Value::String { val, .. } => match val.trim().strip_suffix('%') {
+            Some(percent) => {
+                let percent: f64 = percent.trim().parse().map_err(|_| invalid())?;
+                if !percent.is_finite() || percent <= 0.0 {
+                    return Err(invalid());
+                }
+                return Ok(((terminal() as f64 * percent / 100.0) as usize).max(1));
+            }
+            None => val.trim().parse().map_err(|_| invalid())?,
+        },
+        _ => return Err(invalid()),
+    };
+
+    match usize::try_from(columns) {
+        Ok(columns) => Ok(columns),
+        Err(_) => Ok(terminal()
+            .saturating_sub(columns.unsigned_abs() as usize)
+            .max(1)),
+    }
+}
 
-        Ok(do_wrap(input, optimal, width, call.head))
+/// How the wrapped lines are laid out, on top of the textwrap options.
+struct Layout {
+    align: Alignment,
+    reflow: bool,
+    markdown: bool,
+    prefix: Option<Prefix>,
+}
+
+enum Prefix {
+    Auto,
+    Literal(String),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Alignment {
+    Left,
+    Right,
+    Center,
+    Justify,
+}
+
+impl Alignment {
+    fn from_name(name: Spanned<String>) -> Result<Self, LabeledError> {
+        match name.item.as_str() {
+            "left" => Ok(Alignment::Left),
+            "right" => Ok(Alignment::Right),
+            "center" => Ok(Alignment::Center),
+            "justify" => Ok(Alignment::Justify),
+            _ => Err(LabeledError::new("Invalid alignment")
+                .with_label("expected left, right, center or justify", name.span)),
+        }
+    }
+}
+
+fn word_separator(name: Spanned<String>) -> Result<WordSeparator, LabeledError> {
+    match name.item.as_str() {
+        "ascii" => Ok(WordSeparator::AsciiSpace),
+        "unicode-linebreak" => Ok(WordSeparator::UnicodeBreakProperties),
+        _ => Err(LabeledError::new("Invalid word separator")
+            .with_label("expected ascii or unicode-linebreak", name.span)),
+    }
+}
+
+fn word_splitter(name: Spanned<String>) -> Result<WordSplitter, LabeledError> {
+    match name.item.as_str() {
+        "none" => Ok(WordSplitter::NoHyphenation),
+        "hyphen" => Ok(WordSplitter::HyphenSplitter),
+        "hyphenation" => Ok(WordSplitter::Hyphenation(load_dictionary(
+            "en-us", name.span,
+        )?)),
+        _ => Err(LabeledError::new("Invalid word splitter")
+            .with_label("expected none, hyphen or hyphenation", name.span)),
+    }
+}
+
+fn line_ending_from(name: Spanned<String>) -> Result<LineEnding, LabeledError> {
+    match name.item.as_str() {
+        "lf" => Ok(LineEnding::LF),
+        "crlf" => Ok(LineEnding::CRLF),
+        _ => {
+            Err(LabeledError::new("Invalid line ending")
+                .with_label("expected lf or crlf", name.span))
+        }
     }
 }
 
-fn do_wrap(input: &Value, optimal: bool, width: usize, head: Span) -> Value {
-    let options = Options::new(width).wrap_algorithm(if optimal {
-        WrapAlgorithm::new_optimal_fit()
-    } else {
-        WrapAlgorithm::FirstFit
-    });
+fn do_wrap(
+    input: &Value,
+    options: &Options,
+    layout: &Layout,
+    columns: &[(CellPath, Option<usize>)],
+    head: Span,
+) -> Value {
+    if columns.is_empty() {
+        return operate(input, &[], head, &|value| {
+            wrap_value(value, options, layout, head)
+        });
+    }
+    // each column can have its own width, so the columns are wrapped one after another
+    columns.iter().fold(input.clone(), |value, (path, width)| {
+        let options = match width {
+            Some(width) => options.clone().width(*width),
+            None => options.clone(),
+        };
+        operate(&value, slice::from_ref(path), head, &|cell| {
+            do_wrap(cell, &options, layout, &[], head)
+        })
+    })
+}
 
+fn wrap_value(input: &Value, options: &Options, layout: &Layout, head: Span) -> Value {
     match input {
-        // fill returns a string with the text wrapped at the specified width
-        // wrap returns a list of strings with the text wrapped at the specified width
-        Value::String { val, .. } => Value::string(fill(val, options), head),
-        Value::Error { .. } => input.clone(),
+        Value::String { val, .. } => Value::string(layout_text(val, options, layout), head),
         _ => Value::error(
             ShellError::OnlySupportsThisInputType {
                 exp_input_type: "string".into(),
@@ -97,6 +524,585 @@ fn do_wrap(input: &Value, optimal: bool, width: usize, head: Span) -> Value {
     }
 }
 
+/// The lines of the input, which can end in LF or CRLF. --line-ending only sets the line
+/// ending of the output.
+fn input_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+}
+
+fn layout_text(text: &str, options: &Options, layout: &Layout) -> String {
+    match &layout.prefix {
+        Some(prefix) => prefixed_text(text, options, layout, prefix),
+        None if layout.markdown => markdown_text(text, options, layout.align),
+        None if layout.reflow => reflow_text(text, options, layout.align),
+        None => wrap_text(text, options, layout.align),
+    }
+}
+
+fn prefixed_text(text: &str, options: &Options, layout: &Layout, prefix: &Prefix) -> String {
+    let line_ending = options.line_ending.as_str();
+    // consecutive lines with the same prefix, ignoring trailing whitespace so that blank
+    // comment lines like `///` stay in their block
+    let mut groups: Vec<(&str, Vec<&str>)> = vec![];
+
+    for line in input_lines(text) {
+        let line_prefix = match prefix {
+            Prefix::Auto => detect_prefix(line),
+            Prefix::Literal(prefix) if line.starts_with(prefix.as_str()) => prefix,
+            Prefix::Literal(prefix)
+                if !prefix.trim_end().is_empty() && line.trim_end() == prefix.trim_end() =>
+            {
+                line
+            }
+            Prefix::Literal(_) => "",
+        };
+        let content = &line[line_prefix.len()..];
+
+        match groups.last_mut() {
+            Some((group_prefix, lines)) if group_prefix.trim_end() == line_prefix.trim_end() => {
+                if line_prefix.len() > group_prefix.len() {
+                    *group_prefix = line_prefix;
+                }
+                lines.push(content);
+            }
+            _ => groups.push((line_prefix, vec![content])),
+        }
+    }
+
+    let inner = Layout {
+        align: layout.align,
+        reflow: layout.reflow,
+        markdown: layout.markdown,
+        prefix: None,
+    };
+    groups
+        .iter()
+        .flat_map(|(prefix, lines)| {
+            let width = options.width.saturating_sub(display_width(prefix)).max(1);
+            let text = layout_text(
+                &lines.join(line_ending),
+                &options.clone().width(width),
+                &inner,
+            );
+            text.split(line_ending)
+                .map(|line| match line {
+                    "" => prefix.trim_end().to_string(),
+                    _ => format!("{prefix}{line}"),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .join(line_ending)
+}
+
+/// The comment or quote prefix of a line: leading whitespace, then `///`, `//!`, `//`, a run of
+/// `#`, `;` or `%`, `--`, or nested `>` quotes, and a single space after it.
+fn detect_prefix(line: &str) -> &str {
+    let content = line.trim_start();
+    let mut len = line.len() - content.len();
+    let rest = &line[len..];
+
+    if rest.starts_with('>') {
+        for (i, c) in rest.char_indices() {
+            match c {
+                '>' => len = line.len() - rest.len() + i + 1,
+                ' ' if rest[..i].ends_with('>') => len += 1,
+                _ => break,
+            }
+        }
+        return &line[..len];
+    }
+
+    let token = ["///", "//!", "//", "--"]
+        .iter()
+        .find(|token| rest.starts_with(*token))
+        .map(|token| token.len())
+        .or_else(|| {
+            let first = rest
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '#' | ';' | '%'))?;
+            Some(rest.len() - rest.trim_start_matches(first).len())
+        });
+    match token {
+        Some(token) => {
+            len += token;
+            if line[len..].starts_with(' ') {
+                len += 1;
+            }
+            &line[..len]
+        }
+        // plain lines keep their indentation as part of the text
+        None => "",
+    }
+}
+
+pub(crate) fn wrap_text(text: &str, options: &Options, align: Alignment) -> String {
+    let line_ending = options.line_ending.as_str();
+    let mut style = AnsiStyle::default();
+    let mut lines = vec![];
+
+    // wrap each input line on its own so justify knows where a paragraph ends. like textwrap's
+    // fill, only the very first line gets the initial indent
+    for (p, paragraph) in input_lines(text).enumerate() {
+        let paragraph_options = if p == 0 {
+            options.clone()
+        } else {
+            options.clone().initial_indent(options.subsequent_indent)
+        };
+        if paragraph.is_empty() {
+            lines.push(String::new());
+            continue;
+        }
+        let wrapped = wrap(paragraph, &paragraph_options);
+        let last = wrapped.len().saturating_sub(1);
+        for (i, line) in wrapped.iter().enumerate() {
+            let indent = if i == 0 {
+                paragraph_options.initial_indent
+            } else {
+                paragraph_options.subsequent_indent
+            };
+            let line = style.restyle(line, indent);
+            lines.push(align_line(&line, indent, options.width, align, i == last));
+        }
+    }
+
+    lines.join(line_ending)
+}
+
+/// A group of input lines that is either rewrapped or kept as it is when reflowing.
+enum Block<'a> {
+    Blank,
+    Verbatim(&'a str),
+    Paragraph {
+        initial: String,
+        subsequent: String,
+        text: String,
+        // list items keep their hanging indent, other paragraphs take the indent of line two
+        list_item: bool,
+        lines: usize,
+        // a markdown hard line break ends the paragraph at this line
+        hard_break: bool,
+    },
+}
+
+fn reflow_text(text: &str, options: &Options, align: Alignment) -> String {
+    let mut blocks: Vec<Block> = vec![];
+    for line in input_lines(text) {
+        push_line(&mut blocks, line, false);
+    }
+
+    render_blocks(&blocks, options, align)
+}
+
+/// Add a line to the paragraph it continues, or start a new block with it.
+fn push_line<'a>(blocks: &mut Vec<Block<'a>>, line: &'a str, hard_breaks: bool) {
+    let content = line.trim_start();
+    let indent = &line[..line.len() - content.len()];
+    if content.is_empty() {
+        blocks.push(Block::Blank);
+        return;
+    }
+
+    let marker = list_marker(content);
+    let hard_break = hard_breaks && (line.ends_with("  ") || line.ends_with('\\'));
+    match blocks.last_mut() {
+        Some(Block::Paragraph {
+            subsequent,
+            text,
+            list_item,
+            lines,
+            hard_break: ends,
+            ..
+        }) if marker.is_none() && !*ends => {
+            if !*list_item && *lines == 1 {
+                *subsequent = indent.to_string();
+            }
+            text.push(' ');
+            text.push_str(content.trim_end());
+            *lines += 1;
+            *ends = hard_break;
+        }
+        _ if marker.is_none() && (indent.contains('\t') || indent.len() >= 4) => {
+            blocks.push(Block::Verbatim(line));
+        }
+        _ => {
+            let (initial, content) = match marker {
+                Some(len) => (
+                    format!("{indent}{} ", content[..len].trim_end()),
+                    &content[len..],
+                ),
+                None => (indent.to_string(), content),
+            };
+            let subsequent = match marker {
+                Some(_) => " ".repeat(display_width(&initial)),
+                None => initial.clone(),
+            };
+            blocks.push(Block::Paragraph {
+                initial,
+                subsequent,
+                text: content.trim_end().to_string(),
+                list_item: marker.is_some(),
+                lines: 1,
+                hard_break,
+            });
+        }
+    }
+}
+
+fn render_blocks(blocks: &[Block], options: &Options, align: Alignment) -> String {
+    blocks
+        .iter()
+        .map(|block| match block {
+            Block::Blank => String::new(),
+            Block::Verbatim(line) => line.to_string(),
+            Block::Paragraph {
+                initial,
+                subsequent,
+                text,
+                hard_break,
+                ..
+            } => {
+                let initial = format!("{}{initial}", options.initial_indent);
+                let subsequent = format!("{}{subsequent}", options.subsequent_indent);
+                let options = options
+                    .clone()
+                    .initial_indent(&initial)
+                    .subsequent_indent(&subsequent);
+                let mut wrapped = wrap_text(text, &options, align);
+                // wrapping drops trailing spaces, so put back a two space line break
+                if *hard_break && !text.ends_with('\\') {
+                    wrapped.push_str("  ");
+                }
+                wrapped
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(options.line_ending.as_str())
+}
+
+/// Rewrap the paragraphs and list items of a Markdown document. Code blocks, headings, rules,
+/// tables, HTML blocks, block quotes and link reference definitions are kept line for line.
+fn markdown_text(text: &str, options: &Options, align: Alignment) -> String {
+    let lines: Vec<&str> = input_lines(text).collect();
+    let mut blocks: Vec<Block> = vec![];
+    let mut fence: Option<(char, usize)> = None;
+    let mut html = false;
+    let mut table = false;
+
+    for (i, line) in lines.iter().enumerate() {
+        let content = line.trim_start();
+        if let Some((c, len)) = fence {
+            blocks.push(Block::Verbatim(line));
+            let closes = fence_marker(content).is_some_and(|(close, n)| {
+                close == c && n >= len && content.trim_end().chars().all(|x| x == c)
+            });
+            if closes {
+                fence = None;
+            }
+            continue;
+        }
+        if content.is_empty() {
+            html = false;
+            table = false;
+            blocks.push(Block::Blank);
+            continue;
+        }
+        if html || (table && content.contains('|')) {
+            blocks.push(Block::Verbatim(line));
+            continue;
+        }
+        table = false;
+
+        let indent = &line[..line.len() - content.len()];
+        let in_paragraph = matches!(
+            blocks.last(),
+            Some(Block::Paragraph {
+                hard_break: false,
+                ..
+            })
+        );
+        // anything indented by four columns continues a paragraph or is indented code
+        if !indent.contains('\t') && indent.len() < 4 {
+            if let Some(marker) = fence_marker(content) {
+                fence = Some(marker);
+            } else if !in_paragraph && is_html_start(content) {
+                html = true;
+            } else if (content.contains('|')
+                && lines
+                    .get(i + 1)
+                    .is_some_and(|next| is_table_delimiter(next)))
+                || (!in_paragraph && content.starts_with('|'))
+            {
+                table = true;
+            }
+
+            if fence.is_some()
+                || html
+                || table
+                || content.starts_with('>')
+                || is_heading(content)
+                || is_thematic_break(content)
+                || (in_paragraph && is_setext_underline(content))
+                || (!in_paragraph && is_reference_definition(content))
+            {
+                blocks.push(Block::Verbatim(line));
+                continue;
+            }
+        }
+
+        push_line(&mut blocks, line, true);
+    }
+
+    // only break lines at spaces, so inline code, links and long urls stay in one piece
+    let options = options
+        .clone()
+        .word_separator(WordSeparator::Custom(markdown_words))
+        .word_splitter(WordSplitter::NoHyphenation)
+        .break_words(false);
+    render_blocks(&blocks, &options, align)
+}
+
+/// The character and length of a ``` or ~~~ code fence at the start of `line`.
+fn fence_marker(line: &str) -> Option<(char, usize)> {
+    let c = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = line.len() - line.trim_start_matches(c).len();
+    (len >= 3).then_some((c, len))
+}
+
+fn is_heading(line: &str) -> bool {
+    let rest = line.trim_start_matches('#');
+    (1..=6).contains(&(line.len() - rest.len()))
+        && (rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+fn is_thematic_break(line: &str) -> bool {
+    let mut marks = line.chars().filter(|c| !matches!(c, ' ' | '\t'));
+    match marks.next() {
+        Some(c @ ('-' | '*' | '_')) => {
+            marks.try_fold(1, |n, m| (m == c).then_some(n + 1)) >= Some(3)
+        }
+        _ => false,
+    }
+}
+
+fn is_setext_underline(line: &str) -> bool {
+    let line = line.trim_end();
+    !line.is_empty() && (line.chars().all(|c| c == '=') || line.chars().all(|c| c == '-'))
+}
+
+/// A line that opens an HTML block: a comment, declaration, processing instruction, or an
+/// opening or closing tag.
+fn is_html_start(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix('<') else {
+        return false;
+    };
+    if rest.starts_with(['!', '?']) {
+        return true;
+    }
+    let rest = rest.strip_prefix('/').unwrap_or(rest);
+    let name = rest.trim_start_matches(|c: char| c.is_ascii_alphanumeric() || c == '-');
+    rest.starts_with(|c: char| c.is_ascii_alphabetic())
+        && (name.is_empty() || name.starts_with([' ', '\t', '>', '/']))
+}
+
+/// A link reference definition such as `[label]: https://example.com`.
+fn is_reference_definition(line: &str) -> bool {
+    line.strip_prefix('[')
+        .and_then(|rest| rest.find("]:"))
+        .is_some_and(|label| label > 0)
+}
+
+/// The row under a table header, such as `| --- | :---: |`.
+fn is_table_delimiter(line: &str) -> bool {
+    let line = line.trim();
+    let cells = line.trim_start_matches('|').trim_end_matches('|');
+    line.contains('|')
+        && cells.split('|').all(|cell| {
+            let cell = cell.trim();
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+        })
+}
+
+/// Split a line into words at spaces, keeping inline code spans and link destinations whole.
+fn markdown_words(line: &str) -> Box<dyn Iterator<Item = Word<'_>> + '_> {
+    let mut words = vec![];
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < line.len() {
+        let rest = &line[i..];
+        if rest.starts_with('`') {
+            let run = backtick_run(rest);
+            i += run + code_span_end(&rest[run..], run).unwrap_or(0);
+        } else if let Some(destination) = rest.strip_prefix("](") {
+            i += 2 + link_destination_end(destination).unwrap_or(0);
+        } else if rest.starts_with(' ') {
+            i += rest.len() - rest.trim_start_matches(' ').len();
+            words.push(Word::from(&line[start..i]));
+            start = i;
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    if start < line.len() {
+        words.push(Word::from(&line[start..]));
+    }
+
+    Box::new(words.into_iter())
+}
+
+fn backtick_run(text: &str) -> usize {
+    text.len() - text.trim_start_matches('`').len()
+}
+
+/// Byte offset just past the backtick run of length `run` that closes a code span, if any.
+/// Without one the backticks are literal text.
+fn code_span_end(text: &str, run: usize) -> Option<usize> {
+    let mut i = 0;
+    while let Some(start) = text[i..].find('`') {
+        let len = backtick_run(&text[i + start..]);
+        if len == run {
+            return Some(i + start + len);
+        }
+        i += start + len;
+    }
+    None
+}
+
+/// Byte offset just past the `)` that closes a link destination, allowing nested parentheses.
+fn link_destination_end(text: &str) -> Option<usize> {
+    let mut depth = 1;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Byte length of a bullet (`-`, `*`, `+`, `•`) or number (`1.`, `2)`) list marker at the start
+/// of `line`, including the whitespace after it.
+fn list_marker(line: &str) -> Option<usize> {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let marker = match line[digits..].chars().next()? {
+        '.' | ')' if (1..=9).contains(&digits) => digits + 1,
+        '-' | '*' | '+' | '•' if digits == 0 => line.chars().next()?.len_utf8(),
+        _ => return None,
+    };
+    let rest = &line[marker..];
+    let content = rest.trim_start();
+    (content.len() < rest.len() && !content.is_empty()).then(|| line.len() - content.len())
+}
+
+const SGR_RESET: &str = "\x1b[0m";
+const HYPERLINK_CLOSE: &str = "\x1b]8;;\x1b\\";
+
+/// The ANSI SGR styles and OSC 8 hyperlink that are active after a wrapped line, so that each
+/// line can close them at the end and the next line can open them again.
+#[derive(Default)]
+struct AnsiStyle {
+    sgr: Vec<String>,
+    link: Option<String>,
+}
+
+impl AnsiStyle {
+    fn restyle(&mut self, line: &str, indent: &str) -> String {
+        let (indent, content) = match line.strip_prefix(indent) {
+            Some(content) => (indent, content),
+            None => ("", line),
+        };
+
+        let mut result = indent.to_string();
+        self.sgr.iter().for_each(|sgr| result.push_str(sgr));
+        if let Some(link) = &self.link {
+            result.push_str(link);
+        }
+        self.update(content);
+        result.push_str(content);
+        if self.link.is_some() {
+            result.push_str(HYPERLINK_CLOSE);
+        }
+        if !self.sgr.is_empty() {
+            result.push_str(SGR_RESET);
+        }
+
+        result
+    }
+
+    fn update(&mut self, text: &str) {
+        let mut rest = text;
+        while let Some(start) = rest.find('\x1b') {
+            rest = &rest[start..];
+            let len = escape_len(rest);
+            let sequence = &rest[..len];
+
+            if let Some(params) = sequence
+                .strip_prefix("\x1b[")
+                .and_then(|s| s.strip_suffix('m'))
+            {
+                let first = params.split([';', ':']).next().unwrap_or_default();
+                if first.chars().all(|c| c == '0') {
+                    self.sgr.clear();
+                }
+                if !params.chars().all(|c| c == '0' || c == ';') {
+                    self.sgr.push(sequence.to_string());
+                }
+            } else if let Some(link) = sequence.strip_prefix("\x1b]8;") {
+                // the uri follows the parameters and ends at the string terminator
+                let uri = link
+                    .split_once(';')
+                    .map(|(_, uri)| uri.trim_end_matches(['\x07', '\\', '\x1b']))
+                    .unwrap_or_default();
+                self.link = (!uri.is_empty()).then(|| sequence.to_string());
+            }
+
+            rest = &rest[len..];
+        }
+    }
+}
+
+fn align_line(line: &str, indent: &str, width: usize, align: Alignment, last: bool) -> String {
+    let padding = width.saturating_sub(display_width(line));
+    match align {
+        Alignment::Left => line.to_string(),
+        Alignment::Right => format!("{}{line}", " ".repeat(padding)),
+        Alignment::Center => format!("{}{line}", " ".repeat(padding / 2)),
+        Alignment::Justify if last => line.to_string(),
+        Alignment::Justify => {
+            let content = line.strip_prefix(indent).unwrap_or(line);
+            let words: Vec<&str> = content.split(' ').filter(|w| !w.is_empty()).collect();
+            let gaps = words.len().saturating_sub(1);
+            if gaps == 0 {
+                return line.to_string();
+            }
+
+            // spread the spaces evenly, giving the leftmost gaps any that remain
+            let spaces = width.saturating_sub(
+                display_width(indent) + words.iter().map(|w| display_width(w)).sum::<usize>(),
+            );
+            let (each, extra) = (spaces.max(gaps) / gaps, spaces.max(gaps) % gaps);
+            let mut justified = indent.to_string();
+            for (i, word) in words.iter().enumerate() {
+                if i > 0 {
+                    justified.push_str(&" ".repeat(each + usize::from(i <= extra)));
+                }
+                justified.push_str(word);
+            }
+            justified
+        }
+    }
+}
+
 #[test]
 fn test_examples() -> Result<(), nu_protocol::ShellError> {
     use nu_plugin_test_support::PluginTest;
@@ -109,3 +1115,247 @@ fn test_examples() -> Result<(), nu_protocol::ShellError> {
 
     PluginTest::new("strutils", StrutilsPlugin.into())?.test_command_examples(&StrWrap)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(width: usize) -> Options<'static> {
+        Options::new(width).wrap_algorithm(WrapAlgorithm::FirstFit)
+    }
+
+    #[test]
+    fn test_left_align_matches_fill() {
+        let text = "now is the time for all good men\n\nto come to the aid of their country";
+        for options in [
+            options(10),
+            options(20).subsequent_indent("  "),
+            options(80),
+        ] {
+            assert_eq!(
+                wrap_text(text, &options, Alignment::Left),
+                textwrap::fill(text, &options)
+            );
+        }
+    }
+
+    #[test]
+    fn test_styles_are_reopened_after_each_break() {
+        let text = "\x1b[1m\x1b[32mgreen bold text\x1b[39m plain bold\x1b[0m done";
+        assert_eq!(
+            wrap_text(text, &options(10), Alignment::Left),
+            "\x1b[1m\x1b[32mgreen bold\x1b[0m\n\
+             \x1b[1m\x1b[32mtext\x1b[39m plain\x1b[0m\n\
+             \x1b[1m\x1b[32m\x1b[39mbold\x1b[0m done"
+        );
+    }
+
+    #[test]
+    fn test_hyperlinks_are_reopened_after_each_break() {
+        let link = "\x1b]8;;https://nushell.sh\x1b\\";
+        let text = format!("{link}the nushell website{HYPERLINK_CLOSE} rocks");
+        assert_eq!(
+            wrap_text(&text, &options(12), Alignment::Left),
+            format!("{link}the nushell{HYPERLINK_CLOSE}\n{link}website{HYPERLINK_CLOSE}\nrocks")
+        );
+    }
+
+    #[test]
+    fn test_reflow_keeps_lists_and_blocks() {
+        let text = "Now is the time for\nall good men to come\nto the aid of their country.\n\n\
+                    - first item that was\n  wrapped narrowly\n- second item\n\
+                    10. numbered item that goes on and on\n\n    fn main() {}\n\n\
+                    Hanging paragraph\n    with deeper lines\n    that continue";
+        assert_eq!(
+            reflow_text(text, &options(30), Alignment::Left),
+            "Now is the time for all good\nmen to come to the aid of\ntheir country.\n\n\
+             - first item that was wrapped\n  narrowly\n- second item\n\
+             10. numbered item that goes on\n    and on\n\n    fn main() {}\n\n\
+             Hanging paragraph with deeper\n    lines that continue"
+        );
+    }
+
+    #[test]
+    fn test_prefixed_blocks_are_wrapped_separately() {
+        let layout = Layout {
+            align: Alignment::Left,
+            reflow: true,
+            markdown: false,
+            prefix: Some(Prefix::Auto),
+        };
+        let text = "    /// Now is the time for all good men to come\n    /// to the aid.\n    ///\n\
+                    > > quoted text that is long enough\n> > to wrap\n> single quote";
+        assert_eq!(
+            layout_text(text, &options(30), &layout),
+            "    /// Now is the time for\n    /// all good men to come\n    /// to the aid.\n    ///\n\
+             > > quoted text that is long\n> > enough to wrap\n> single quote"
+        );
+    }
+
+    #[test]
+    fn test_line_ending_converts_input_lines() {
+        let crlf = options(3).line_ending(LineEnding::CRLF);
+        assert_eq!(
+            wrap_text("aa bb\ncc dd", &crlf, Alignment::Left),
+            "aa\r\nbb\r\ncc\r\ndd"
+        );
+        assert_eq!(
+            wrap_text("aa bb\r\ncc dd", &crlf, Alignment::Left),
+            "aa\r\nbb\r\ncc\r\ndd"
+        );
+        assert_eq!(
+            wrap_text("aa bb\r\ncc", &options(3), Alignment::Left),
+            "aa\nbb\ncc"
+        );
+        let text = "# aa bb\n# cc\n\naa bb";
+        for (reflow, markdown) in [(true, false), (false, true)] {
+            let layout = Layout {
+                align: Alignment::Left,
+                reflow,
+                markdown,
+                prefix: Some(Prefix::Auto),
+            };
+            assert_eq!(
+                layout_text(text, &options(5).line_ending(LineEnding::CRLF), &layout),
+                "# aa\r\n# bb\r\n# cc\r\n\r\naa bb"
+            );
+        }
+    }
+
+    #[test]
+    fn test_markdown_keeps_blocks() {
+        let text = "## A heading that is far too long to fit\n\n\
+                    ~~~\nlet code = \"that is far too long to fit\";\n~~~\n\n\
+                    | table | with a row that is too long |\n| --- | --- |\n| a | b |\n\n\
+                    <p>\nan html block that is too long\n</p>\n\n\
+                    [label]: https://example.com/a/very/long/link/target\n\
+                    > a block quote that is far too long to fit";
+        assert_eq!(markdown_text(text, &options(20), Alignment::Left), text);
+    }
+
+    #[test]
+    fn test_markdown_wraps_paragraphs_and_list_items() {
+        let text = "Setext heading\n===\n\
+                    a paragraph with a\nsoft break and a hard  \nbreak\n\n\
+                    - a list item with `inline code` in it\n\
+                    - see [the nu book](https://www.nushell.sh/book/)";
+        assert_eq!(
+            markdown_text(text, &options(20), Alignment::Left),
+            "Setext heading\n===\n\
+             a paragraph with a\nsoft break and a\nhard  \nbreak\n\n\
+             - a list item with\n  `inline code` in\n  it\n\
+             - see [the nu\n  book](https://www.nushell.sh/book/)"
+        );
+    }
+
+    #[test]
+    fn test_markdown_words() {
+        let words = |line| {
+            markdown_words(line)
+                .map(|word| word.word)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            words("a `b c` d [e f](g \"h i\") j"),
+            ["a", "`b c`", "d", "[e", "f](g \"h i\")", "j"]
+        );
+        assert_eq!(words("``a ` b`` c"), ["``a ` b``", "c"]);
+        // unmatched backticks and brackets are plain text
+        assert_eq!(words("a ` b ](c d"), ["a", "`", "b", "](c", "d"]);
+    }
+
+    #[test]
+    fn test_markdown_block_starts() {
+        assert!(is_heading("### heading") && is_heading("#") && !is_heading("#hashtag"));
+        assert!(!is_heading("####### seven"));
+        assert!(is_thematic_break("* * *") && is_thematic_break("___"));
+        assert!(!is_thematic_break("- item") && !is_thematic_break("--"));
+        assert!(is_html_start("<div class=\"x\">") && is_html_start("</p>"));
+        assert!(is_html_start("<!-- comment -->") && !is_html_start("<https://nushell.sh>"));
+        assert!(is_table_delimiter("|:---|---:|") && is_table_delimiter("--- | ---"));
+        assert!(!is_table_delimiter("---") && !is_table_delimiter("| a | b |"));
+        assert!(is_reference_definition("[nu]: https://nushell.sh"));
+        assert!(!is_reference_definition("[]: x") && !is_reference_definition("[link](x)"));
+        assert_eq!(fence_marker("````rust"), Some(('`', 4)));
+        assert_eq!(fence_marker("``code``"), None);
+    }
+
+    #[test]
+    fn test_wrap_table_columns() {
+        let layout = Layout {
+            align: Alignment::Left,
+            reflow: false,
+            markdown: false,
+            prefix: None,
+        };
+        let row = |name, description| {
+            Value::test_record(record! {
+                "name" => Value::test_string(name),
+                "description" => Value::test_string(description),
+            })
+        };
+        let column = |name: &str| CellPath {
+            members: vec![PathMember::test_string(
+                name.into(),
+                false,
+                Casing::Sensitive,
+            )],
+        };
+        let table = Value::test_list(vec![row("nushell plugin", "a new type of shell")]);
+
+        assert_eq!(
+            do_wrap(
+                &table,
+                &options(10),
+                &layout,
+                &[(column("description"), None)],
+                Span::test_data()
+            ),
+            Value::test_list(vec![row("nushell plugin", "a new type\nof shell")])
+        );
+        assert_eq!(
+            do_wrap(
+                &table,
+                &options(80),
+                &layout,
+                &[(column("name"), Some(8)), (column("description"), None)],
+                Span::test_data()
+            ),
+            Value::test_list(vec![row("nushell\nplugin", "a new type of shell")])
+        );
+    }
+
+    #[test]
+    fn test_resolve_width() {
+        let width = |value| resolve_width(&value, || 100);
+        assert_eq!(width(Value::test_int(30)).ok(), Some(30));
+        assert_eq!(width(Value::test_int(-4)).ok(), Some(96));
+        assert_eq!(width(Value::test_int(-200)).ok(), Some(1));
+        assert_eq!(width(Value::test_string("50%")).ok(), Some(50));
+        assert_eq!(width(Value::test_string("12.5%")).ok(), Some(12));
+        assert_eq!(width(Value::test_string("-10")).ok(), Some(90));
+        assert!(width(Value::test_string("0%")).is_err());
+        assert!(width(Value::test_string("wide")).is_err());
+        assert!(width(Value::test_float(1.5)).is_err());
+    }
+
+    #[test]
+    fn test_detect_prefix() {
+        assert_eq!(detect_prefix("/// doc"), "/// ");
+        assert_eq!(detect_prefix("  ## heading"), "  ## ");
+        assert_eq!(detect_prefix("-- sql"), "-- ");
+        assert_eq!(detect_prefix("> > quote"), "> > ");
+        assert_eq!(detect_prefix(">> quote"), ">> ");
+        assert_eq!(detect_prefix("///"), "///");
+        assert_eq!(detect_prefix("  plain"), "");
+    }
+
+    #[test]
+    fn test_list_marker() {
+        assert_eq!(list_marker("- item"), Some(2));
+        assert_eq!(list_marker("•  item"), Some(5));
+        assert_eq!(list_marker("12) item"), Some(4));
+        assert_eq!(list_marker("-item"), None);
+        assert_eq!(list_marker("3.14 is pi"), None);
+    }
+}
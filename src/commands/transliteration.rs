@@ -0,0 +1,472 @@
+use nu_protocol::{LabeledError, Spanned};
+
+/// Transliteration rules of one language, mapping lowercase sequences to ASCII.
+pub(crate) struct Language {
+    pub(crate) code: &'static str,
+    pub(crate) rules: &'static [(&'static str, &'static str)],
+    /// Rules that take precedence at the start of a word.
+    pub(crate) word_start: &'static [(&'static str, &'static str)],
+    /// Rules that take precedence right after one of the lowercase characters, as
+    /// `(characters, key, replacement)`.
+    pub(crate) after: &'static [(&'static str, &'static str, &'static str)],
+    /// Rules that take precedence right before one of the lowercase characters or at the end of
+    /// a word, as `(characters, key, replacement)`.
+    pub(crate) before: &'static [(&'static str, &'static str, &'static str)],
+}
+
+pub(crate) const LANGUAGES: &[Language] = &[
+    Language {
+        code: "de",
+        rules: &[
+            ("ä", "ae"),
+            ("ö", "oe"),
+            ("ü", "ue"),
+            ("ß", "ss"),
+            ("a\u{308}", "ae"),
+            ("o\u{308}", "oe"),
+            ("u\u{308}", "ue"),
+        ],
+        word_start: &[],
+        after: &[],
+        before: &[],
+    },
+    Language {
+        code: "da",
+        rules: NORDIC,
+        word_start: &[],
+        after: &[],
+        before: &[],
+    },
+    Language {
+        code: "nb",
+        rules: NORDIC,
+        word_start: &[],
+        after: &[],
+        before: &[],
+    },
+    Language {
+        code: "no",
+        rules: NORDIC,
+        word_start: &[],
+        after: &[],
+        before: &[],
+    },
+    // ELOT 743 without diacritics
+    Language {
+        code: "el",
+        rules: &[
+            ("αυ", "av"),
+            ("αύ", "av"),
+            ("ευ", "ev"),
+            ("εύ", "ev"),
+            ("ηυ", "iv"),
+            ("ηύ", "iv"),
+            ("ου", "ou"),
+            ("ού", "ou"),
+            ("γγ", "ng"),
+            ("γξ", "nx"),
+            ("γχ", "nch"),
+            ("μπ", "mb"),
+            ("ντ", "nd"),
+            ("α", "a"),
+            ("ά", "a"),
+            ("β", "v"),
+            ("γ", "g"),
+            ("δ", "d"),
+            ("ε", "e"),
+            ("έ", "e"),
+            ("ζ", "z"),
+            ("η", "i"),
+            ("ή", "i"),
+            ("θ", "th"),
+            ("ι", "i"),
+            ("ί", "i"),
+            ("ϊ", "i"),
+            ("ΐ", "i"),
+            ("κ", "k"),
+            ("λ", "l"),
+            ("μ", "m"),
+            ("ν", "n"),
+            ("ξ", "x"),
+            ("ο", "o"),
+            ("ό", "o"),
+            ("π", "p"),
+            ("ρ", "r"),
+            ("σ", "s"),
+            ("ς", "s"),
+            ("τ", "t"),
+            ("υ", "y"),
+            ("ύ", "y"),
+            ("ϋ", "y"),
+            ("ΰ", "y"),
+            ("φ", "f"),
+            ("χ", "ch"),
+            ("ψ", "ps"),
+            ("ω", "o"),
+            ("ώ", "o"),
+        ],
+        word_start: &[("μπ", "b"), ("ντ", "d")],
+        after: &[],
+        // υ is written f before voiceless consonants and at the end of a word
+        before: &[
+            (VOICELESS, "αυ", "af"),
+            (VOICELESS, "αύ", "af"),
+            (VOICELESS, "ευ", "ef"),
+            (VOICELESS, "εύ", "ef"),
+            (VOICELESS, "ηυ", "if"),
+            (VOICELESS, "ηύ", "if"),
+        ],
+    },
+    // BGN/PCGN without diacritics
+    Language {
+        code: "ru",
+        rules: &[
+            ("а", "a"),
+            ("б", "b"),
+            ("в", "v"),
+            ("г", "g"),
+            ("д", "d"),
+            ("е", "e"),
+            ("ё", "yo"),
+            ("ж", "zh"),
+            ("з", "z"),
+            ("и", "i"),
+            ("й", "y"),
+            ("к", "k"),
+            ("л", "l"),
+            ("м", "m"),
+            ("н", "n"),
+            ("о", "o"),
+            ("п", "p"),
+            ("р", "r"),
+            ("с", "s"),
+            ("т", "t"),
+            ("у", "u"),
+            ("ф", "f"),
+            ("х", "kh"),
+            ("ц", "ts"),
+            ("ч", "ch"),
+            ("ш", "sh"),
+            ("щ", "shch"),
+            ("ъ", "\""),
+            ("ы", "y"),
+            ("ь", "'"),
+            ("э", "e"),
+            ("ю", "yu"),
+            ("я", "ya"),
+        ],
+        word_start: &[("е", "ye")],
+        after: &[("аеёиоуыэюяйъь", "е", "ye")],
+        before: &[],
+    },
+    // the Ukrainian national system of 2010
+    Language {
+        code: "uk",
+        rules: &[
+            ("зг", "zgh"),
+            ("а", "a"),
+            ("б", "b"),
+            ("в", "v"),
+            ("г", "h"),
+            ("ґ", "g"),
+            ("д", "d"),
+            ("е", "e"),
+            ("є", "ie"),
+            ("ж", "zh"),
+            ("з", "z"),
+            ("и", "y"),
+            ("і", "i"),
+            ("ї", "i"),
+            ("й", "i"),
+            ("к", "k"),
+            ("л", "l"),
+            ("м", "m"),
+            ("н", "n"),
+            ("о", "o"),
+            ("п", "p"),
+            ("р", "r"),
+            ("с", "s"),
+            ("т", "t"),
+            ("у", "u"),
+            ("ф", "f"),
+            ("х", "kh"),
+            ("ц", "ts"),
+            ("ч", "ch"),
+            ("ш", "sh"),
+            ("щ", "shch"),
+            ("ь", ""),
+            ("ю", "iu"),
+            ("я", "ia"),
+            ("'", ""),
+            ("’", ""),
+            ("ʼ", ""),
+        ],
+        word_start: &[
+            ("є", "ye"),
+            ("ї", "yi"),
+            ("й", "y"),
+            ("ю", "yu"),
+            ("я", "ya"),
+        ],
+        after: &[],
+        before: &[],
+    },
+    // the Bulgarian streamlined system
+    Language {
+        code: "bg",
+        rules: &[
+            ("а", "a"),
+            ("б", "b"),
+            ("в", "v"),
+            ("г", "g"),
+            ("д", "d"),
+            ("е", "e"),
+            ("ж", "zh"),
+            ("з", "z"),
+            ("и", "i"),
+            ("й", "y"),
+            ("к", "k"),
+            ("л", "l"),
+            ("м", "m"),
+            ("н", "n"),
+            ("о", "o"),
+            ("п", "p"),
+            ("р", "r"),
+            ("с", "s"),
+            ("т", "t"),
+            ("у", "u"),
+            ("ф", "f"),
+            ("х", "h"),
+            ("ц", "ts"),
+            ("ч", "ch"),
+            ("ш", "sh"),
+            ("щ", "sht"),
+            ("ъ", "a"),
+            ("ь", "y"),
+            ("ю", "yu"),
+            ("я", "ya"),
+        ],
+        word_start: &[],
+        after: &[],
+        before: &[],
+    },
+    // ISO 9 system B, the ASCII only variant also known as GOST 7.79-2000 B
+    Language {
+        code: "iso9",
+        rules: &[
+            ("це", "cze"),
+            ("ци", "czi"),
+            ("цы", "czy'"),
+            ("цй", "czj"),
+            ("а", "a"),
+            ("б", "b"),
+            ("в", "v"),
+            ("г", "g"),
+            ("ґ", "g`"),
+            ("ѓ", "g`"),
+            ("д", "d"),
+            ("ђ", "dj"),
+            ("е", "e"),
+            ("ё", "yo"),
+            ("є", "ye"),
+            ("ж", "zh"),
+            ("з", "z"),
+            ("ѕ", "z`"),
+            ("и", "i"),
+            ("і", "i"),
+            ("ї", "yi"),
+            ("й", "j"),
+            ("ј", "j"),
+            ("к", "k"),
+            ("ќ", "k`"),
+            ("л", "l"),
+            ("љ", "l`"),
+            ("м", "m"),
+            ("н", "n"),
+            ("њ", "n`"),
+            ("о", "o"),
+            ("п", "p"),
+            ("р", "r"),
+            ("с", "s"),
+            ("т", "t"),
+            ("ћ", "c`"),
+            ("у", "u"),
+            ("ў", "u`"),
+            ("ф", "f"),
+            ("х", "x"),
+            ("ц", "c"),
+            ("ч", "ch"),
+            ("џ", "dh"),
+            ("ш", "sh"),
+            ("щ", "shh"),
+            ("ъ", "``"),
+            ("ы", "y'"),
+            ("ь", "`"),
+            ("э", "e`"),
+            ("ю", "yu"),
+            ("я", "ya"),
+        ],
+        word_start: &[],
+        after: &[],
+        before: &[],
+    },
+];
+
+const NORDIC: &[(&str, &str)] = &[("æ", "ae"), ("ø", "oe"), ("å", "aa"), ("a\u{30A}", "aa")];
+
+/// The Greek voiceless consonants.
+const VOICELESS: &str = "θκξπστφχψς";
+
+/// Look up the language for a `--lang` flag.
+pub(crate) fn language(code: &Spanned<String>) -> Result<&'static Language, LabeledError> {
+    LANGUAGES
+        .iter()
+        .find(|language| language.code.eq_ignore_ascii_case(&code.item))
+        .ok_or_else(|| {
+            let codes: Vec<_> = LANGUAGES.iter().map(|language| language.code).collect();
+            LabeledError::new("Unknown language")
+                .with_label(format!("expected one of {}", codes.join(", ")), code.span)
+        })
+}
+
+impl Language {
+    /// The replacement for the longest rule matching `text` at byte `i`, and the number of
+    /// bytes it replaces. The case of the replacement follows the case of the matched text.
+    pub(crate) fn replace(&self, text: &str, i: usize) -> Option<(usize, String)> {
+        let rest = &text[i..];
+        let previous = text[..i].chars().next_back();
+        let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+        let matches =
+            |(key, replacement): (&str, &'static str)| Some((match_len(rest, key)?, replacement));
+        let word_start = match previous {
+            Some(c) if c.is_alphanumeric() => &[],
+            _ => self.word_start,
+        };
+        let after = self
+            .after
+            .iter()
+            .filter(|(characters, ..)| previous.is_some_and(|c| characters.contains(lower(c))))
+            .map(|&(_, key, replacement)| (key, replacement));
+        let before = self
+            .before
+            .iter()
+            .filter_map(|&(characters, key, replacement)| {
+                let len = match_len(rest, key)?;
+                match rest[len..].chars().next() {
+                    Some(c) if c.is_alphanumeric() && !characters.contains(lower(c)) => None,
+                    _ => Some((len, replacement)),
+                }
+            });
+        // rules with a context come first, so they win between matches of the same length
+        let (len, replacement) = word_start
+            .iter()
+            .copied()
+            .chain(after)
+            .filter_map(matches)
+            .chain(before)
+            .chain(self.rules.iter().copied().filter_map(matches))
+            .reduce(|longest, rule| if rule.0 > longest.0 { rule } else { longest })?;
+
+        let matched = &text[i..i + len];
+        let next = text[i + len..].chars().next();
+        let upper = |c: Option<char>| c.is_some_and(char::is_uppercase);
+        let mut chars = matched.chars();
+        let replacement = match chars.next() {
+            Some(first) if first.is_uppercase() => {
+                let all_upper = chars.clone().next().is_some() && chars.all(char::is_uppercase);
+                let in_upper_word =
+                    upper(next) || (upper(previous) && !next.is_some_and(char::is_lowercase));
+                if all_upper || in_upper_word {
+                    replacement.to_uppercase()
+                } else {
+                    let mut rest = replacement.chars();
+                    rest.next()
+                        .map(|c| c.to_uppercase().chain(rest).collect())
+                        .unwrap_or_default()
+                }
+            }
+            _ => replacement.to_string(),
+        };
+
+        Some((len, replacement))
+    }
+}
+
+/// The byte length of the prefix of `text` that lowercases to `key`.
+fn match_len(text: &str, key: &str) -> Option<usize> {
+    let mut len = 0;
+    let mut chars = text.chars();
+    for k in key.chars() {
+        let c = chars.next()?;
+        let mut lower = c.to_lowercase();
+        if lower.next() != Some(k) || lower.next().is_some() {
+            return None;
+        }
+        len += c.len_utf8();
+    }
+
+    Some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transliterate(code: &str, text: &str) -> String {
+        let language = LANGUAGES.iter().find(|l| l.code == code).unwrap();
+        let mut result = String::new();
+        let mut i = 0;
+        while let Some(c) = text[i..].chars().next() {
+            match language.replace(text, i) {
+                Some((len, replacement)) => {
+                    result.push_str(&replacement);
+                    i += len;
+                }
+                None => {
+                    result.push(c);
+                    i += c.len_utf8();
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_case_follows_the_text() {
+        assert_eq!(transliterate("de", "Übergröße"), "Uebergroesse");
+        assert_eq!(transliterate("de", "MÜLLER"), "MUELLER");
+        assert_eq!(transliterate("de", "ẞ"), "Ss");
+        assert_eq!(transliterate("ru", "Щука ЩУКА"), "Shchuka SHCHUKA");
+    }
+
+    #[test]
+    fn test_word_start_rules() {
+        assert_eq!(transliterate("uk", "Юрій Київ"), "Yurii Kyiv");
+        assert_eq!(transliterate("ru", "Елена Федорова"), "Yelena Fedorova");
+        assert_eq!(transliterate("el", "μπαμπάς"), "bambas");
+    }
+
+    #[test]
+    fn test_context_rules() {
+        assert_eq!(transliterate("el", "αυτό"), "afto");
+        assert_eq!(transliterate("el", "ευχαριστώ"), "efcharisto");
+        assert_eq!(transliterate("el", "ΑΥΤΟΚΙΝΗΤΟ"), "AFTOKINITO");
+        assert_eq!(transliterate("el", "Ζευς"), "Zefs");
+        assert_eq!(transliterate("el", "αυλή ευ"), "avli ef");
+        assert_eq!(transliterate("ru", "Алексеев"), "Alekseyev");
+        assert_eq!(transliterate("ru", "подъезд"), "pod\"yezd");
+        assert_eq!(transliterate("ru", "Ульяновск объём"), "Ul'yanovsk ob\"yom");
+        assert_eq!(transliterate("ru", "лес"), "les");
+    }
+
+    #[test]
+    fn test_longest_rule_wins() {
+        assert_eq!(transliterate("iso9", "Цирк царь"), "Czirk car`");
+        assert_eq!(transliterate("el", "Ευρώπη"), "Evropi");
+        assert_eq!(transliterate("uk", "Згорани"), "Zghorany");
+        assert_eq!(
+            transliterate("iso9", "Његош Ђурђевић"),
+            "N`egosh Djurdjevic`"
+        );
+    }
+}
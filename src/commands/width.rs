@@ -0,0 +1,110 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Display width of a line in terminal columns. Wide characters such as CJK and emoji take two
+/// columns, a grapheme cluster like an emoji joined with zero width joiners counts once, and ANSI
+/// escape sequences and control characters take none.
+pub(crate) fn display_width(line: &str) -> usize {
+    segments(line).iter().map(|(_, width)| width).sum()
+}
+
+/// Display width of the widest line of `text`.
+pub(crate) fn text_width(text: &str) -> usize {
+    text.lines().map(display_width).max().unwrap_or(0)
+}
+
+/// Apply `f` to each line of `text` without its line ending, which is kept. Empty text is a
+/// single empty line.
+pub(crate) fn map_lines(text: &str, f: impl Fn(&str) -> String) -> String {
+    if text.is_empty() {
+        return f(text);
+    }
+    text.split_inclusive('\n')
+        .map(|line| {
+            let content = line.trim_end_matches(['\n', '\r']);
+            f(content) + &line[content.len()..]
+        })
+        .collect()
+}
+
+/// Split a line into grapheme clusters and ANSI escape sequences, each with its display width.
+pub(crate) fn segments(line: &str) -> Vec<(&str, usize)> {
+    let mut segments = vec![];
+    let mut rest = line;
+    while !rest.is_empty() {
+        let text_len = rest.find('\x1b').unwrap_or(rest.len());
+        segments.extend(
+            rest[..text_len]
+                .graphemes(true)
+                .map(|grapheme| (grapheme, grapheme_width(grapheme))),
+        );
+        rest = &rest[text_len..];
+        if !rest.is_empty() {
+            let len = escape_len(rest);
+            segments.push((&rest[..len], 0));
+            rest = &rest[len..];
+        }
+    }
+
+    segments
+}
+
+/// The grapheme cluster at the start of `text`, which must not be empty, and its display width.
+pub(crate) fn next_grapheme(text: &str) -> (&str, usize) {
+    let grapheme = text.graphemes(true).next().unwrap_or(text);
+    (grapheme, grapheme_width(grapheme))
+}
+
+/// Display width of a grapheme cluster, with control characters taking no columns.
+fn grapheme_width(grapheme: &str) -> usize {
+    match grapheme.chars().all(char::is_control) {
+        true => 0,
+        false => grapheme.width(),
+    }
+}
+
+/// Byte length of the escape sequence at the start of `text`, which must start with ESC.
+pub(crate) fn escape_len(text: &str) -> usize {
+    let mut chars = text.char_indices().skip(1);
+    match chars.next() {
+        // CSI sequences end with a final byte in the range 0x40-0x7E
+        Some((_, '[')) => chars
+            .find(|(_, c)| ('\x40'..='\x7e').contains(c))
+            .map_or(text.len(), |(i, c)| i + c.len_utf8()),
+        // OSC sequences end with BEL or the string terminator ESC \
+        Some((_, ']')) => {
+            let mut prev = ']';
+            chars
+                .find(|&(_, c)| {
+                    let end = c == '\x07' || (prev == '\x1b' && c == '\\');
+                    prev = c;
+                    end
+                })
+                .map_or(text.len(), |(i, c)| i + c.len_utf8())
+        }
+        Some((i, c)) => i + c.len_utf8(),
+        None => text.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_width() {
+        assert_eq!(text_width(""), 0);
+        assert_eq!(text_width("\x1b[31mred\x1b[0m"), 3);
+        assert_eq!(text_width("a\tb\u{200b}"), 2);
+        assert_eq!(text_width("🇩🇪 👍🏽"), 5);
+        assert_eq!(text_width("short\nlonger line\r\n"), 11);
+        assert_eq!(display_width("👨‍👩‍👧"), 2);
+    }
+
+    #[test]
+    fn test_escape_len() {
+        assert_eq!(escape_len("\x1b[38;5;208mtext"), 11);
+        assert_eq!(escape_len("\x1b]8;;http://a\x07text"), 14);
+        assert_eq!(escape_len("\x1b]8;;\x1b\\text"), 7);
+    }
+}
@@ -1,3 +1,7 @@
+// Every command tests its examples with a function returning `Result<(), ShellError>`, the error
+// type of the plugin test support, which is larger than clippy likes.
+#![cfg_attr(test, allow(clippy::result_large_err))]
+
 use nu_plugin::{serve_plugin, MsgPackSerializer, Plugin, PluginCommand};
 
 mod commands;
@@ -25,6 +29,21 @@ impl Plugin for StrutilsPlugin {
             Box::new(StrSlug),
             Box::new(StrShlSplit),
             Box::new(StrShlQuote),
+            Box::new(StrSuggest),
+            Box::new(StrHyphenate),
+            Box::new(StrColumns),
+            Box::new(StrIndentationDetect),
+            Box::new(StrIndentationConvert),
+            Box::new(StrExpandTabs),
+            Box::new(StrUnexpandTabs),
+            Box::new(StrNormalize),
+            Box::new(StrChars),
+            Box::new(StrConfusables),
+            Box::new(StrSanitize),
+            Box::new(StrWidth),
+            Box::new(StrTruncate),
+            Box::new(StrPad),
+            Box::new(StrFilename),
         ]
     }
 }